@@ -0,0 +1,132 @@
+//! Compares the brute force proximity scans zombies used to do against `SpatialGrid`.
+//!
+//! Run with `cargo bench --bench proximity`. Both sides run the queries that used to scan every
+//! player or zombie: each zombie looking for the nearest player in sight, each player counting the
+//! zombies in its spawn ring, and each zombie checking it is still near a player. Rebuilding the
+//! grids is included in the grid's time, and both sides add up to the same total.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use bevy::math::Vec2;
+use rand::Rng;
+
+#[path = "../src/spatial_grid/mod.rs"]
+#[allow(dead_code)]
+mod spatial_grid;
+
+use spatial_grid::SpatialGrid;
+
+const WORLD_SIZE: f32 = 4000.;
+const PLAYERS: usize = 8;
+const SIGHT_DISTANCE: f32 = 150.;
+const SPAWN_RANGE: f32 = 700.;
+const ZOMBIE_CELL_SIZE: f32 = 64.;
+const PLAYER_CELL_SIZE: f32 = 256.;
+const ITERATIONS: u32 = 20;
+
+fn main() {
+    for zombies in [1_000, 2_000, 5_000, 10_000] {
+        let player_positions = random_positions(PLAYERS);
+        let zombie_positions = around_players(&player_positions, zombies);
+
+        let brute_force = time(|| {
+            let mut total = 0;
+            for zombie in &zombie_positions {
+                total += nearest(
+                    player_positions.iter().copied().enumerate(),
+                    *zombie,
+                    SIGHT_DISTANCE,
+                );
+                total += player_positions
+                    .iter()
+                    .any(|p| p.distance(*zombie) < SPAWN_RANGE) as usize;
+            }
+            for player in &player_positions {
+                total += zombie_positions
+                    .iter()
+                    .filter(|z| z.distance(*player) < SPAWN_RANGE)
+                    .count();
+            }
+            total
+        });
+
+        // Kept across frames and cleared, like the game's grids.
+        let mut zombie_grid = SpatialGrid::new(ZOMBIE_CELL_SIZE);
+        let mut player_grid = SpatialGrid::new(PLAYER_CELL_SIZE);
+        let grid = time(|| {
+            zombie_grid.clear();
+            player_grid.clear();
+            for (index, position) in zombie_positions.iter().enumerate() {
+                zombie_grid.insert(index, *position);
+            }
+            for (index, position) in player_positions.iter().enumerate() {
+                player_grid.insert(index, *position);
+            }
+
+            let mut total = 0;
+            for zombie in &zombie_positions {
+                let in_sight = player_grid.query(*zombie, SIGHT_DISTANCE);
+                total += nearest(in_sight, *zombie, SIGHT_DISTANCE);
+                total += player_grid.query(*zombie, SPAWN_RANGE).next().is_some() as usize;
+            }
+            for player in &player_positions {
+                total += zombie_grid.count(*player, SPAWN_RANGE);
+            }
+            total
+        });
+
+        println!(
+            "{zombies:>6} zombies: brute force {:>10.3?}, grid {:>10.3?} ({:.1}x)",
+            brute_force,
+            grid,
+            brute_force.as_secs_f64() / grid.as_secs_f64()
+        );
+    }
+}
+
+/// One past the index of the closest player within `radius` of `from`, or 0 when there is none.
+fn nearest(players: impl Iterator<Item = (usize, Vec2)>, from: Vec2, radius: f32) -> usize {
+    players
+        .filter(|(_, p)| p.distance(from) < radius)
+        .min_by(|(_, a), (_, b)| {
+            a.distance_squared(from)
+                .total_cmp(&b.distance_squared(from))
+        })
+        .map_or(0, |(index, _)| index + 1)
+}
+
+fn random_positions(count: usize) -> Vec<Vec2> {
+    let mut rng = rand::rng();
+    (0..count)
+        .map(|_| {
+            Vec2::new(
+                rng.random_range(0.0..WORLD_SIZE),
+                rng.random_range(0.0..WORLD_SIZE),
+            )
+        })
+        .collect()
+}
+
+/// Zombies only live in the spawn ring around players, so they are spread the same way here.
+fn around_players(players: &[Vec2], count: usize) -> Vec<Vec2> {
+    let mut rng = rand::rng();
+    (0..count)
+        .map(|_| {
+            let player = players[rng.random_range(0..players.len())];
+            let angle = rng.random_range(0.0..std::f32::consts::TAU);
+            player + Vec2::from_angle(angle) * rng.random_range(0.0..SPAWN_RANGE)
+        })
+        .collect()
+}
+
+/// Average time of one frame's worth of queries.
+fn time(mut frame: impl FnMut() -> usize) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(frame());
+    }
+    start.elapsed() / ITERATIONS
+}
@@ -19,6 +19,10 @@ impl CameraFollow {
     pub fn new(follow: Entity, speed: f32) -> CameraFollow {
         CameraFollow { follow, speed }
     }
+
+    pub fn set_follow(&mut self, follow: Entity) {
+        self.follow = follow;
+    }
 }
 
 fn camera_follow(
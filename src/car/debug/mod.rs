@@ -0,0 +1,113 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::{tire::Tire, Car};
+
+pub struct VehicleDebugPlugin;
+
+impl Plugin for VehicleDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VehicleDebug>().add_systems(
+            Update,
+            (toggle_debug, draw_car_debug, spawn_labels, update_labels).chain(),
+        );
+    }
+}
+
+/// Draws tire forces and handling values over every car. Toggled with F3, or inserted enabled to
+/// start with the overlay on.
+#[derive(Resource, Default)]
+pub struct VehicleDebug {
+    pub enabled: bool,
+}
+
+#[derive(Component)]
+struct DebugLabel {
+    target: Entity,
+}
+
+fn toggle_debug(keys: Res<ButtonInput<KeyCode>>, mut debug: ResMut<VehicleDebug>) {
+    if keys.just_pressed(KeyCode::F3) {
+        debug.enabled = !debug.enabled;
+    }
+}
+
+fn draw_car_debug(
+    mut gizmos: Gizmos,
+    debug: Res<VehicleDebug>,
+    cars: Query<(&GlobalTransform, &LinearVelocity), With<Car>>,
+) {
+    if !debug.enabled {
+        return;
+    }
+    for (gt, velocity) in cars.iter() {
+        let position = gt.translation().xy();
+        gizmos.arrow_2d(
+            position,
+            position + **velocity / 4.,
+            Color::srgb(0., 1., 0.),
+        );
+    }
+}
+
+fn spawn_labels(
+    mut commands: Commands,
+    debug: Res<VehicleDebug>,
+    targets: Query<Entity, Or<(With<Car>, With<Tire>)>>,
+    labels: Query<(Entity, &DebugLabel)>,
+) {
+    if !debug.enabled {
+        for (label, _) in labels.iter() {
+            commands.entity(label).despawn();
+        }
+        return;
+    }
+    for target in targets.iter() {
+        if labels.iter().any(|(_, label)| label.target == target) {
+            continue;
+        }
+        commands.spawn((
+            DebugLabel { target },
+            Text2d::new(""),
+            TextFont {
+                font_size: 8.,
+                ..default()
+            },
+        ));
+    }
+}
+
+fn update_labels(
+    mut commands: Commands,
+    mut labels: Query<(Entity, &DebugLabel, &mut Text2d, &mut Transform)>,
+    cars: Query<(&GlobalTransform, &LinearVelocity, &AngularVelocity), With<Car>>,
+    tires: Query<(&GlobalTransform, &Tire)>,
+) {
+    for (label, DebugLabel { target }, mut text, mut transform) in labels.iter_mut() {
+        let (position, offset, content) =
+            if let Ok((gt, velocity, angular_velocity)) = cars.get(*target) {
+                (
+                    gt.translation(),
+                    Vec3::new(0., 28., 0.),
+                    format!("v {:.0} w {:.2}", velocity.length(), **angular_velocity),
+                )
+            } else if let Ok((gt, tire)) = tires.get(*target) {
+                (
+                    gt.translation(),
+                    Vec3::new(0., -8., 0.),
+                    format!(
+                        "{:.0}° {:.2} x{:.2}",
+                        tire.slip_angle.to_degrees(),
+                        tire.grip,
+                        tire.load
+                    ),
+                )
+            } else {
+                commands.entity(label).despawn();
+                continue;
+            };
+
+        transform.translation = (position + offset).with_z(10.);
+        text.0 = content;
+    }
+}
@@ -1,20 +1,34 @@
 use bevy::prelude::*;
+use debug::VehicleDebugPlugin;
+use skid_marks::SkidMarksPlugin;
+use steering::SteeringPlugin;
 use tire::TirePlugin;
 
+pub mod debug;
+pub mod skid_marks;
+pub mod steering;
+pub mod surface;
 pub mod tire;
 pub struct CarPlugin;
 
 impl Plugin for CarPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(TirePlugin);
+        app.add_plugins((
+            TirePlugin,
+            SteeringPlugin,
+            SkidMarksPlugin,
+            VehicleDebugPlugin,
+        ));
     }
 }
 
 #[derive(Component)]
-#[require(Transform)]
+#[require(Transform, WeightTransfer, CarInput)]
 pub struct Car {
-    current_power: f32,
+    pub current_power: f32,
     pub max_power: f32,
+    /// Boost meter from `0.` to `1.`.
+    pub boost: f32,
 }
 
 impl Car {
@@ -22,6 +36,83 @@ impl Car {
         Car {
             current_power,
             max_power,
+            boost: 0.,
         }
     }
+
+    pub fn add_boost(&mut self, amount: f32) {
+        self.boost = (self.boost + amount).clamp(0., 1.);
+    }
+}
+
+/// Driver controls of a car, written from the keyboard for the local player and by the AI for bots.
+#[derive(Component, Default)]
+pub struct CarInput {
+    /// `1.` for full throttle forward, `-1.` for reverse.
+    pub throttle: f32,
+    /// `-1..=1`, positive turns left.
+    pub steer: f32,
+    pub drift: bool,
+    pub boost: bool,
+    pub reset: bool,
+    pub horn: bool,
+}
+
+/// Fuel tank of a car. Throttling burns `consumption` per second and the engine stalls when empty.
+#[derive(Component)]
+pub struct Fuel {
+    pub amount: f32,
+    pub capacity: f32,
+    consumption: f32,
+}
+
+impl Fuel {
+    pub fn new(capacity: f32, consumption: f32) -> Fuel {
+        Fuel {
+            amount: capacity,
+            capacity,
+            consumption,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.amount <= 0.
+    }
+
+    pub fn fraction(&self) -> f32 {
+        self.amount / self.capacity
+    }
+
+    pub fn burn(&mut self, seconds: f32) {
+        self.amount = (self.amount - self.consumption * seconds).max(0.);
+    }
+
+    pub fn refuel(&mut self, amount: f32) {
+        self.amount = (self.amount + amount).min(self.capacity);
+    }
+}
+
+/// Smoothed acceleration of a car in its own frame, used to shift normal load between tires.
+/// `transfer` is how much load moves per unit of acceleration.
+#[derive(Component)]
+pub struct WeightTransfer {
+    pub transfer: f32,
+    acceleration: Vec2,
+    previous_velocity: Vec2,
+}
+
+impl WeightTransfer {
+    pub fn new(transfer: f32) -> WeightTransfer {
+        WeightTransfer {
+            transfer,
+            acceleration: Vec2::ZERO,
+            previous_velocity: Vec2::ZERO,
+        }
+    }
+}
+
+impl Default for WeightTransfer {
+    fn default() -> Self {
+        WeightTransfer::new(1. / 1200.)
+    }
 }
@@ -0,0 +1,71 @@
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+
+use crate::{
+    particles::{spawn_smoke, Particle},
+    rng::random_float,
+};
+
+use super::tire::Tire;
+
+const SKID_SLIP_THRESHOLD: f32 = 60.;
+const SMOKE_SLIP_THRESHOLD: f32 = 120.;
+const SKID_MARK_LIFETIME: f32 = 12.;
+const MAX_SKID_MARKS: usize = 600;
+
+pub struct SkidMarksPlugin;
+
+impl Plugin for SkidMarksPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SkidMarks>().add_systems(
+            Update,
+            spawn_skid_marks.run_if(on_timer(Duration::from_millis(50))),
+        );
+    }
+}
+
+/// Skid marks in spawn order, so the oldest can be removed once `MAX_SKID_MARKS` is reached.
+#[derive(Resource, Default)]
+struct SkidMarks(VecDeque<Entity>);
+
+fn spawn_skid_marks(
+    mut commands: Commands,
+    mut skid_marks: ResMut<SkidMarks>,
+    tires: Query<(&GlobalTransform, &Tire)>,
+) {
+    for (gt, tire) in tires.iter() {
+        if tire.slip < SKID_SLIP_THRESHOLD {
+            continue;
+        }
+        let (_, rotation, translation) = gt.to_scale_rotation_translation();
+        let alpha = ((tire.slip - SKID_SLIP_THRESHOLD) / SKID_SLIP_THRESHOLD).clamp(0.2, 0.7);
+
+        let mark = commands
+            .spawn((
+                Transform::from_translation(translation.xy().extend(-0.4)).with_rotation(rotation),
+                Sprite::from_color(Color::srgba(0.05, 0.05, 0.05, alpha), Vec2::new(3., 6.)),
+                Particle::new(SKID_MARK_LIFETIME, Vec2::ZERO, 0., alpha),
+            ))
+            .id();
+        skid_marks.0.push_back(mark);
+
+        if tire.slip > SMOKE_SLIP_THRESHOLD && random_float(0.0..1.) < 0.5 {
+            spawn_smoke(
+                &mut commands,
+                translation.xy(),
+                Color::srgba(0.85, 0.85, 0.85, 0.4),
+                5.,
+            );
+        }
+    }
+
+    while skid_marks.0.len() > MAX_SKID_MARKS {
+        let Some(oldest) = skid_marks.0.pop_front() else {
+            break;
+        };
+        if let Some(mut entity) = commands.get_entity(oldest) {
+            entity.despawn();
+        }
+    }
+}
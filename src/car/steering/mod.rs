@@ -0,0 +1,83 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::utils::move_towards;
+
+use super::{tire::Tire, CarInput};
+
+pub struct SteeringPlugin;
+
+impl Plugin for SteeringPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (update_steering, turn_tires).chain());
+    }
+}
+
+/// Steering state of a car. `angle` follows `CarInput::steer` at `rate` per second and is scaled by
+/// each tire's `turning_radius`.
+#[derive(Component)]
+#[require(Transform)]
+pub struct Steering {
+    angle: f32,
+    rate: f32,
+    high_speed_lock: f32,
+    lock_speed: f32,
+    self_centering: Option<f32>,
+}
+
+impl Steering {
+    /// `high_speed_lock` is the fraction of the full steering lock left at `lock_speed` and above.
+    pub fn new(rate: f32, high_speed_lock: f32, lock_speed: f32) -> Steering {
+        Steering {
+            angle: 0.,
+            rate,
+            high_speed_lock,
+            lock_speed,
+            self_centering: None,
+        }
+    }
+
+    /// Returns the wheel to center at `rate` per second when there is no input.
+    pub fn with_self_centering(mut self, rate: f32) -> Steering {
+        self.self_centering = Some(rate);
+        self
+    }
+
+    pub fn angle(&self) -> f32 {
+        self.angle
+    }
+
+    fn lock(&self, speed: f32) -> f32 {
+        let t = (speed / self.lock_speed).clamp(0., 1.);
+        1. + (self.high_speed_lock - 1.) * t
+    }
+}
+
+fn update_steering(time: Res<Time>, mut cars: Query<(&mut Steering, &CarInput, &LinearVelocity)>) {
+    for (mut steering, car_input, velocity) in cars.iter_mut() {
+        let lock = steering.lock(velocity.length());
+        let input = car_input.steer.clamp(-1., 1.);
+        let (target, rate) = if input != 0. {
+            (input * lock, steering.rate)
+        } else if let Some(centering) = steering.self_centering {
+            (0., centering)
+        } else {
+            (steering.angle.clamp(-lock, lock), steering.rate)
+        };
+
+        steering.angle = move_towards(steering.angle, target, rate * time.delta_secs());
+    }
+}
+
+fn turn_tires(cars: Query<&Steering>, mut tires: Query<(&Parent, &mut Transform, &Tire)>) {
+    for (car_entity, mut transform, tire) in tires.iter_mut() {
+        let Some(turning_radius) = tire.turning_radius else {
+            continue;
+        };
+        let Ok(steering) = cars.get(**car_entity) else {
+            continue;
+        };
+
+        transform.rotation = Quat::from_rotation_z((turning_radius * steering.angle).to_radians());
+    }
+}
@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+
+/// Ground a tire can drive on. Surfaces are sensor colliders; a tire overlapping several of them
+/// uses the most slippery one and falls back to `Surface::ASPHALT` when it overlaps none.
+#[derive(Component, Clone, Copy)]
+pub struct Surface {
+    pub grip: f32,
+    pub rolling_resistance: f32,
+}
+
+impl Surface {
+    pub const ASPHALT: Surface = Surface::new(1., 1.);
+    pub const GRASS: Surface = Surface::new(0.6, 3.);
+    pub const BLOOD: Surface = Surface::new(0.5, 1.);
+    pub const OIL: Surface = Surface::new(0.15, 0.8);
+
+    pub const fn new(grip: f32, rolling_resistance: f32) -> Surface {
+        Surface {
+            grip,
+            rolling_resistance,
+        }
+    }
+}
@@ -1,13 +1,30 @@
 use avian2d::prelude::*;
 use bevy::prelude::*;
 
-use super::Car;
+use super::{debug::VehicleDebug, surface::Surface, Car, CarInput, Fuel, WeightTransfer};
+
+const LOAD_SENSITIVITY: f32 = 0.8;
+const ACCELERATION_SMOOTHING: f32 = 10.;
+const WEAR_PER_SLIP: f32 = 0.00015;
+const WEAR_PER_DISTANCE: f32 = 0.000005;
+const WORN_GRIP: f32 = 0.5;
+const WORN_ROLLING_RESISTANCE: f32 = 2.;
 
 pub struct TirePlugin;
 
 impl Plugin for TirePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (rolling_resistance, grip, power));
+        // Forces are applied once per fixed tick right before avian's step in `FixedPostUpdate`,
+        // which integrates them over the timestep, so they are not scaled by it here.
+        app.add_systems(
+            FixedUpdate,
+            (
+                (sample_surfaces, weight_transfer),
+                (rolling_resistance, grip, power),
+                wear,
+            )
+                .chain(),
+        );
     }
 }
 
@@ -18,6 +35,17 @@ pub struct Tire {
     pub turning_radius: Option<f32>,
     rolling_resistance: f32,
     pub grip: f32,
+    /// Grip of the tire before drifting and damage are applied.
+    pub base_grip: f32,
+    /// Lateral speed of the tire over the ground, updated by `grip`.
+    pub slip: f32,
+    /// Angle in radians between the tire's heading and its velocity over the ground.
+    pub slip_angle: f32,
+    /// Share of the car's weight on this tire, `1.` when the car is at rest.
+    pub load: f32,
+    /// How worn down the tire is, from `0.` when new to `1.` when bald.
+    pub wear: f32,
+    surface: Surface,
 }
 
 impl Tire {
@@ -32,6 +60,83 @@ impl Tire {
             turning_radius,
             rolling_resistance,
             grip,
+            base_grip: grip,
+            slip: 0.,
+            slip_angle: 0.,
+            load: 1.,
+            wear: 0.,
+            surface: Surface::ASPHALT,
+        }
+    }
+
+    /// Remaining tread, `1.` for a new tire.
+    pub fn condition(&self) -> f32 {
+        1. - self.wear
+    }
+
+    pub fn repair(&mut self) {
+        self.wear = 0.;
+    }
+
+    fn wear_grip(&self) -> f32 {
+        1. - (1. - WORN_GRIP) * self.wear
+    }
+
+    fn wear_rolling_resistance(&self) -> f32 {
+        1. + (WORN_ROLLING_RESISTANCE - 1.) * self.wear
+    }
+}
+
+fn sample_surfaces(
+    spatial: SpatialQuery,
+    surfaces: Query<&Surface>,
+    mut tires: Query<(&GlobalTransform, &mut Tire)>,
+) {
+    for (gt, mut tire) in tires.iter_mut() {
+        tire.surface = spatial
+            .point_intersections(gt.translation().xy(), &SpatialQueryFilter::default())
+            .into_iter()
+            .filter_map(|entity| surfaces.get(entity).ok())
+            .copied()
+            .min_by(|a, b| a.grip.total_cmp(&b.grip))
+            .unwrap_or(Surface::ASPHALT);
+    }
+}
+
+fn weight_transfer(
+    time: Res<Time>,
+    mut cars: Query<(&Transform, &LinearVelocity, &mut WeightTransfer, &Children)>,
+    mut tires: Query<(&Transform, &mut Tire)>,
+) {
+    let dt = time.delta_secs();
+    if dt <= 0. {
+        return;
+    }
+
+    for (transform, velocity, mut weight, children) in cars.iter_mut() {
+        let acceleration = (**velocity - weight.previous_velocity) / dt;
+        let local = (transform.rotation.inverse() * acceleration.extend(0.)).xy();
+        weight.previous_velocity = **velocity;
+        weight.acceleration = weight
+            .acceleration
+            .lerp(local, (dt * ACCELERATION_SMOOTHING).min(1.));
+
+        let extent = children
+            .iter()
+            .filter_map(|child| tires.get(*child).ok())
+            .fold(Vec2::ZERO, |extent, (t, _)| {
+                extent.max(t.translation.xy().abs())
+            })
+            .max(Vec2::ONE);
+
+        // Load moves against the acceleration: to the rear when speeding up, to the front when
+        // braking and to the outside of a corner.
+        for child in children.iter() {
+            let Ok((tire_transform, mut tire)) = tires.get_mut(*child) else {
+                continue;
+            };
+            let position = tire_transform.translation.xy() / extent;
+            tire.load = (1. - weight.transfer * weight.acceleration.dot(position)).clamp(0., 2.);
         }
     }
 }
@@ -40,33 +145,36 @@ fn rolling_resistance(
     tires: Query<(&Parent, &GlobalTransform, &Tire)>,
     mut cars: Query<(&GlobalTransform, &LinearVelocity, &mut ExternalForce), With<Car>>,
     mut gizmos: Gizmos,
-    time: Res<Time>,
+    debug: Res<VehicleDebug>,
 ) {
     for (car_entity, position, tire) in tires.iter() {
         let Ok((gt, rb, mut force)) = cars.get_mut(**car_entity) else {
             continue;
         };
-        let dir = -**rb;
-        let magnitude = tire.rolling_resistance.min(rb.length());
-        let resistance = dir * magnitude;
-        let forcee = resistance;
+        let resistance = -**rb
+            * tire.rolling_resistance
+            * tire.surface.rolling_resistance
+            * tire.wear_rolling_resistance();
 
         force.apply_force_at_point(
-            forcee * time.delta_secs(),
+            resistance,
             position.translation().xy(),
             gt.translation().xy(),
         );
-        /* gizmos.line_2d(
-            position.translation().xy(),
-            position.translation().xy() + forcee / 10.,
-            Color::srgb(0.5, 0., 0.5),
-        ); */
+        if debug.enabled {
+            gizmos.line_2d(
+                position.translation().xy(),
+                position.translation().xy() + resistance * 6.,
+                Color::srgb(0.5, 0., 0.5),
+            );
+        }
     }
 }
 
 fn grip(
     mut gizmos: Gizmos,
-    tires: Query<(&Parent, &GlobalTransform, &Tire)>,
+    debug: Res<VehicleDebug>,
+    mut tires: Query<(&Parent, &GlobalTransform, &mut Tire)>,
     mut cars: Query<
         (
             &GlobalTransform,
@@ -76,9 +184,8 @@ fn grip(
         ),
         With<Car>,
     >,
-    time: Res<Time>,
 ) {
-    for (car_entity, gt, tire) in tires.iter() {
+    for (car_entity, gt, mut tire) in tires.iter_mut() {
         let Ok((car_transform, velocity, angular_velocity, mut force)) = cars.get_mut(**car_entity)
         else {
             continue;
@@ -87,13 +194,21 @@ fn grip(
         let tire_vel = **velocity + **angular_velocity * offset.xy().perp();
         let side_force = gt.right().xy().dot(tire_vel);
         let new_force = -gt.right().xy() * side_force;
-        /* gizmos.line_2d(
-            gt.translation().xy(),
-            gt.translation().xy() + new_force / 10.,
-            Color::srgb(1., 0., 0.),
-        ); */
+        tire.slip = side_force.abs();
+        tire.slip_angle = side_force.atan2(gt.up().xy().dot(tire_vel).abs());
+        if debug.enabled {
+            gizmos.line_2d(
+                gt.translation().xy(),
+                gt.translation().xy() + new_force / 10.,
+                Color::srgb(1., 0., 0.),
+            );
+        }
         force.apply_force_at_point(
-            new_force * time.delta_secs() * 60. * tire.grip,
+            new_force
+                * tire.grip
+                * tire.wear_grip()
+                * tire.surface.grip
+                * tire.load.powf(LOAD_SENSITIVITY),
             gt.translation().xy(),
             car_transform.translation().xy(),
         );
@@ -102,36 +217,61 @@ fn grip(
 
 fn power(
     mut gizmos: Gizmos,
-    keys: Res<ButtonInput<KeyCode>>,
+    debug: Res<VehicleDebug>,
     tires: Query<(&Parent, &GlobalTransform, &Tire)>,
-    mut cars: Query<(&Car, &GlobalTransform, &mut ExternalForce)>,
+    mut cars: Query<(
+        &Car,
+        &CarInput,
+        Option<&mut Fuel>,
+        &GlobalTransform,
+        &mut ExternalForce,
+    )>,
     time: Res<Time>,
 ) {
-    let dir;
-    if keys.pressed(KeyCode::KeyW) {
-        dir = 1.;
-    } else if keys.pressed(KeyCode::KeyS) {
-        dir = -1.;
-    } else {
-        return;
+    for (_, input, fuel, _, _) in cars.iter_mut() {
+        if let Some(mut fuel) = fuel {
+            fuel.burn(input.throttle.abs() * time.delta_secs());
+        }
     }
 
     for (car_entity, position, tire) in tires.iter() {
         if !tire.current_powered {
-            return;
+            continue;
         }
-        let Ok((car, gt, mut force)) = cars.get_mut(**car_entity) else {
+        let Ok((car, input, fuel, gt, mut force)) = cars.get_mut(**car_entity) else {
             continue;
         };
+        let dir = input.throttle.clamp(-1., 1.);
+        if dir == 0. || fuel.is_some_and(|fuel| fuel.is_empty()) {
+            continue;
+        }
         force.apply_force_at_point(
-            position.up().xy() * car.current_power * dir * time.delta_secs(),
+            position.up().xy() * car.current_power * dir,
             position.translation().xy(),
             gt.translation().xy(),
         );
-        /* gizmos.line_2d(
-            position.translation().xy(),
-            position.translation().xy() + dir,
-            Color::srgb(0., 0., 1.),
-        ); */
+        if debug.enabled {
+            gizmos.line_2d(
+                position.translation().xy(),
+                position.translation().xy() + position.up().xy() * dir * 10.,
+                Color::srgb(0., 0., 1.),
+            );
+        }
+    }
+}
+
+/// Tires wear down with the distance they roll and much faster while sliding.
+fn wear(
+    time: Res<Time>,
+    cars: Query<&LinearVelocity, With<Car>>,
+    mut tires: Query<(&Parent, &mut Tire)>,
+) {
+    for (car_entity, mut tire) in tires.iter_mut() {
+        let Ok(velocity) = cars.get(**car_entity) else {
+            continue;
+        };
+        let distance = velocity.length() * time.delta_secs();
+        let sliding = tire.slip * time.delta_secs();
+        tire.wear = (tire.wear + distance * WEAR_PER_DISTANCE + sliding * WEAR_PER_SLIP).min(1.);
     }
 }
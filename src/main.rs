@@ -5,7 +5,10 @@ use zo::ZOPlugin;
 
 mod camera_follow;
 mod car;
+mod particles;
 mod rng;
+mod spatial_grid;
+mod sprite_animation;
 mod utils;
 mod zo;
 
@@ -16,6 +19,7 @@ fn main() {
             (PhysicsPlugins::default().set(PhysicsInterpolationPlugin::interpolate_all()),),
         )
         .insert_resource(Gravity::ZERO)
+        .insert_resource(Time::<Fixed>::from_hz(60.))
         .add_plugins((SteamP2PPlugin, ZOPlugin))
         .run();
 }
@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+
+use crate::rng::{random_float, random_point_in_donut};
+
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_particles);
+    }
+}
+
+#[derive(Component)]
+#[require(Transform)]
+pub struct Particle {
+    lifetime: Timer,
+    velocity: Vec2,
+    growth: f32,
+    alpha: f32,
+}
+
+impl Particle {
+    pub fn new(lifetime: f32, velocity: Vec2, growth: f32, alpha: f32) -> Particle {
+        Particle {
+            lifetime: Timer::from_seconds(lifetime, TimerMode::Once),
+            velocity,
+            growth,
+            alpha,
+        }
+    }
+}
+
+pub fn spawn_smoke(commands: &mut Commands, position: Vec2, color: Color, size: f32) {
+    let alpha = color.alpha();
+    commands.spawn((
+        Transform::from_translation(position.extend(2.)).with_rotation(Quat::from_rotation_z(
+            random_float(0.0..std::f32::consts::TAU),
+        )),
+        Sprite::from_color(color, Vec2::splat(size)),
+        Particle::new(
+            random_float(0.6..1.2),
+            random_point_in_donut(2., 12.),
+            1.5,
+            alpha,
+        ),
+    ));
+}
+
+fn update_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut particles: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    for (entity, mut particle, mut transform, mut sprite) in particles.iter_mut() {
+        particle.lifetime.tick(time.delta());
+        if particle.lifetime.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation += (particle.velocity * time.delta_secs()).extend(0.);
+        transform.scale += Vec3::splat(particle.growth * time.delta_secs());
+        sprite
+            .color
+            .set_alpha(particle.alpha * particle.lifetime.fraction_remaining());
+    }
+}
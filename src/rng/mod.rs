@@ -19,3 +19,23 @@ pub fn random_float(range: Range<f32>) -> f32 {
 
     return rng.random_range(range);
 }
+
+pub fn random_index(len: usize) -> usize {
+    let mut rng = rand::rng();
+
+    rng.random_range(0..len)
+}
+
+/// Index into `weights`, each picked with a chance proportional to its weight.
+pub fn random_weighted(weights: &[f32]) -> usize {
+    let mut rng = rand::rng();
+
+    let mut roll = rng.random_range(0.0..weights.iter().sum::<f32>());
+    for (index, weight) in weights.iter().enumerate() {
+        if roll < *weight {
+            return index;
+        }
+        roll -= weight;
+    }
+    weights.len() - 1
+}
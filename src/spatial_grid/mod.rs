@@ -0,0 +1,113 @@
+use bevy::{
+    math::{IVec2, Vec2},
+    utils::HashMap,
+};
+
+/// Buckets items by the cell their position falls in, so proximity queries only look at the
+/// cells around the query instead of at every item. Cells should be about the size of the
+/// typical query radius.
+pub struct SpatialGrid<T> {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<(T, Vec2)>>,
+}
+
+impl<T: Copy> SpatialGrid<T> {
+    pub fn new(cell_size: f32) -> SpatialGrid<T> {
+        SpatialGrid {
+            cell_size,
+            cells: HashMap::default(),
+        }
+    }
+
+    fn cell(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    /// Empties the grid, keeping the buckets that were in use so rebuilding every tick doesn't
+    /// reallocate them.
+    pub fn clear(&mut self) {
+        self.cells.retain(|_, items| {
+            let used = !items.is_empty();
+            items.clear();
+            used
+        });
+    }
+
+    pub fn insert(&mut self, item: T, position: Vec2) {
+        let cell = self.cell(position);
+        self.cells.entry(cell).or_default().push((item, position));
+    }
+
+    /// Items within `radius` of `center`, in no particular order.
+    pub fn query(&self, center: Vec2, radius: f32) -> impl Iterator<Item = (T, Vec2)> + '_ {
+        let min = self.cell(center - Vec2::splat(radius));
+        let max = self.cell(center + Vec2::splat(radius));
+        let radius_squared = radius * radius;
+        (min.x..=max.x)
+            .flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .filter(move |(_, position)| position.distance_squared(center) <= radius_squared)
+    }
+
+    pub fn count(&self, center: Vec2, radius: f32) -> usize {
+        self.query(center, radius).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(grid: &SpatialGrid<u32>, center: Vec2, radius: f32) -> Vec<u32> {
+        let mut items: Vec<u32> = grid.query(center, radius).map(|(item, _)| item).collect();
+        items.sort();
+        items
+    }
+
+    #[test]
+    fn query_finds_items_across_cell_boundaries() {
+        let mut grid = SpatialGrid::new(64.);
+        grid.insert(1, Vec2::new(63.9, 10.));
+        grid.insert(2, Vec2::new(64., 10.));
+        grid.insert(3, Vec2::new(64.1, 63.9));
+        assert_eq!(found(&grid, Vec2::new(64., 32.), 32.), vec![1, 2, 3]);
+        assert_eq!(found(&grid, Vec2::new(60., 10.), 4.), vec![1, 2]);
+    }
+
+    #[test]
+    fn query_handles_negative_coordinates() {
+        let mut grid = SpatialGrid::new(64.);
+        grid.insert(1, Vec2::new(-0.5, -0.5));
+        grid.insert(2, Vec2::new(0.5, 0.5));
+        grid.insert(3, Vec2::new(-64., -64.));
+        grid.insert(4, Vec2::new(-200., 5.));
+        assert_eq!(found(&grid, Vec2::ZERO, 1.), vec![1, 2]);
+        assert_eq!(found(&grid, Vec2::new(-63., -63.), 2.), vec![3]);
+        assert_eq!(found(&grid, Vec2::new(-190., 0.), 20.), vec![4]);
+    }
+
+    #[test]
+    fn query_with_radius_larger_than_a_cell() {
+        let mut grid = SpatialGrid::new(16.);
+        grid.insert(1, Vec2::new(100., 0.));
+        grid.insert(2, Vec2::new(0., -100.));
+        grid.insert(3, Vec2::new(-70., 70.));
+        grid.insert(4, Vec2::new(101., 0.));
+        // Inside the searched square of cells but outside the circle.
+        grid.insert(5, Vec2::new(80., 80.));
+        assert_eq!(found(&grid, Vec2::ZERO, 100.), vec![1, 2, 3]);
+        assert_eq!(grid.count(Vec2::ZERO, 100.), 3);
+    }
+
+    #[test]
+    fn clear_removes_every_item() {
+        let mut grid = SpatialGrid::new(64.);
+        grid.insert(1, Vec2::new(10., 10.));
+        grid.clear();
+        assert_eq!(grid.count(Vec2::new(10., 10.), 64.), 0);
+        grid.insert(2, Vec2::new(10., 10.));
+        assert_eq!(found(&grid, Vec2::new(10., 10.), 1.), vec![2]);
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+pub struct SpriteAnimationPlugin;
+
+impl Plugin for SpriteAnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, animate_sprites);
+    }
+}
+
+/// A sequence of texture atlas frames.
+pub struct Clip {
+    pub frames: Vec<usize>,
+    pub fps: f32,
+    pub looping: bool,
+}
+
+impl Clip {
+    pub fn new(frames: Vec<usize>, fps: f32, looping: bool) -> Clip {
+        Clip {
+            frames,
+            fps,
+            looping,
+        }
+    }
+}
+
+/// Plays one of `clips` on the entity's `Sprite` atlas. `speed` scales the playback rate.
+#[derive(Component)]
+#[require(Sprite)]
+pub struct SpriteAnimation {
+    clips: Vec<Clip>,
+    current: usize,
+    frame: usize,
+    progress: f32,
+    pub speed: f32,
+    /// Added to every clip frame, so sprites with their frames elsewhere in the same atlas can
+    /// share the clips.
+    pub offset: usize,
+}
+
+impl SpriteAnimation {
+    pub fn new(clips: Vec<Clip>) -> SpriteAnimation {
+        SpriteAnimation {
+            clips,
+            current: 0,
+            frame: 0,
+            progress: 0.,
+            speed: 1.,
+            offset: 0,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: usize) -> SpriteAnimation {
+        self.offset = offset;
+        self
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Switches to `clip`, restarting it only if it wasn't already playing.
+    pub fn play(&mut self, clip: usize) {
+        if self.current != clip {
+            self.restart(clip);
+        }
+    }
+
+    pub fn restart(&mut self, clip: usize) {
+        self.current = clip;
+        self.frame = 0;
+        self.progress = 0.;
+    }
+
+    /// Whether a clip that doesn't loop has reached its last frame.
+    pub fn is_finished(&self) -> bool {
+        let clip = &self.clips[self.current];
+        !clip.looping && self.frame + 1 >= clip.frames.len()
+    }
+}
+
+fn animate_sprites(time: Res<Time>, mut sprites: Query<(&mut SpriteAnimation, &mut Sprite)>) {
+    for (mut animation, mut sprite) in sprites.iter_mut() {
+        let Some(clip) = animation.clips.get(animation.current) else {
+            continue;
+        };
+        let (fps, frame_count, looping) = (clip.fps, clip.frames.len(), clip.looping);
+        if frame_count == 0 {
+            continue;
+        }
+
+        animation.progress += fps * animation.speed * time.delta_secs();
+        while animation.progress >= 1. {
+            animation.progress -= 1.;
+            if animation.frame + 1 < frame_count {
+                animation.frame += 1;
+            } else if looping {
+                animation.frame = 0;
+            }
+        }
+
+        let index = animation.clips[animation.current].frames[animation.frame] + animation.offset;
+        if let Some(atlas) = &mut sprite.texture_atlas {
+            if atlas.index != index {
+                atlas.index = index;
+            }
+        }
+    }
+}
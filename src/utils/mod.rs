@@ -26,23 +26,9 @@ where
     None
 }
 
-pub fn query_double_mut<'a, D, E, F, G>(
-    q1: &'a mut Query<D, F>,
-    q2: &'a mut Query<E, G>,
-    e1: Entity,
-    e2: Entity,
-) -> Option<(QueryItem<'a, D>, QueryItem<'a, E>)>
-where
-    D: QueryData,
-    E: QueryData,
-    F: QueryFilter,
-    G: QueryFilter,
-{
-    if q1.contains(e1) && q2.contains(e2) {
-        return Some((q1.get_mut(e1).unwrap(), q2.get_mut(e2).unwrap()));
+pub fn move_towards(current: f32, target: f32, max_delta: f32) -> f32 {
+    if (target - current).abs() <= max_delta {
+        return target;
     }
-    if q1.contains(e2) && q2.contains(e1) {
-        return Some((q1.get_mut(e2).unwrap(), q2.get_mut(e1).unwrap()));
-    }
-    None
+    current + (target - current).signum() * max_delta
 }
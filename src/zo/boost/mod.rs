@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_steam_p2p::{
+    networked_events::{event::Networked, register::NetworkedEvents},
+    NetworkId, NetworkIdentity, SteamP2PClient,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    car::{tire::Tire, Car, CarInput},
+    particles::spawn_smoke,
+};
+
+use super::{LocalPlayer, Player};
+
+const DRIFT_SLIP: f32 = 80.;
+const DRIFT_BOOST_RATE: f32 = 0.15;
+const MIN_BOOST: f32 = 0.25;
+const BOOST_SECONDS: f32 = 4.;
+
+pub struct ZOBoostPlugin;
+impl Plugin for ZOBoostPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_networked_event::<BoostActivated>().add_systems(
+            Update,
+            (
+                fill_from_drift,
+                trigger_boost,
+                handle_boost_activated,
+                tick_boosting,
+                exhaust_flames.run_if(on_timer(Duration::from_millis(30))),
+                spawn_boost_gauge,
+                update_boost_gauge,
+            ),
+        );
+    }
+}
+
+/// Present on a car while its boost is active, on every peer.
+#[derive(Component)]
+pub struct Boosting(Timer);
+
+#[derive(Component)]
+struct BoostGauge;
+
+#[derive(Event, Serialize, Clone, Deserialize)]
+pub struct BoostActivated {
+    network_id: NetworkId,
+    seconds: f32,
+}
+
+fn fill_from_drift(
+    time: Res<Time>,
+    client: Res<SteamP2PClient>,
+    mut cars: Query<(&NetworkIdentity, &CarInput, &mut Car, &Children), With<Player>>,
+    tires: Query<&Tire>,
+) {
+    for (identity, input, mut car, children) in cars.iter_mut() {
+        if identity.id.owner != client.id || !input.drift {
+            continue;
+        }
+        let drifting = tires.iter_many(children).any(|tire| tire.slip > DRIFT_SLIP);
+        if drifting {
+            car.add_boost(DRIFT_BOOST_RATE * time.delta_secs());
+        }
+    }
+}
+
+fn trigger_boost(
+    client: Res<SteamP2PClient>,
+    mut cars: Query<(&NetworkIdentity, &CarInput, &mut Car), (With<Player>, Without<Boosting>)>,
+    mut boost_w: EventWriter<Networked<BoostActivated>>,
+) {
+    for (identity, input, mut car) in cars.iter_mut() {
+        if identity.id.owner != client.id || !input.boost || car.boost < MIN_BOOST {
+            continue;
+        }
+        boost_w.send(Networked::new(BoostActivated {
+            network_id: identity.id.clone(),
+            seconds: car.boost * BOOST_SECONDS,
+        }));
+        car.boost = 0.;
+    }
+}
+
+fn handle_boost_activated(
+    mut commands: Commands,
+    mut boost_r: EventReader<BoostActivated>,
+    cars: Query<(Entity, &NetworkIdentity), With<Car>>,
+) {
+    for boost in boost_r.read() {
+        let Some((car, _)) = cars.iter().find(|(_, i)| i.id == boost.network_id) else {
+            continue;
+        };
+        commands.entity(car).insert(Boosting(Timer::from_seconds(
+            boost.seconds,
+            TimerMode::Once,
+        )));
+    }
+}
+
+fn tick_boosting(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut cars: Query<(Entity, &mut Boosting)>,
+) {
+    for (car, mut boosting) in cars.iter_mut() {
+        if boosting.0.tick(time.delta()).finished() {
+            commands.entity(car).remove::<Boosting>();
+        }
+    }
+}
+
+fn exhaust_flames(mut commands: Commands, cars: Query<&GlobalTransform, With<Boosting>>) {
+    for transform in cars.iter() {
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        let exhaust = translation.xy() - (rotation * Vec3::Y).xy() * 16.;
+        spawn_smoke(&mut commands, exhaust, Color::srgba(1., 0.55, 0.1, 0.9), 4.);
+    }
+}
+
+fn spawn_boost_gauge(
+    mut commands: Commands,
+    cars: Query<(), (Added<LocalPlayer>, With<Car>)>,
+    existing: Query<(), With<BoostGauge>>,
+) {
+    if cars.is_empty() || !existing.is_empty() {
+        return;
+    }
+    commands.spawn((
+        BoostGauge,
+        Text::new(""),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(12.),
+            right: Val::Px(12.),
+            ..default()
+        },
+    ));
+}
+
+fn update_boost_gauge(
+    cars: Query<(&Car, Has<Boosting>), With<LocalPlayer>>,
+    mut gauges: Query<&mut Text, With<BoostGauge>>,
+) {
+    let Ok((car, boosting)) = cars.get_single() else {
+        return;
+    };
+    for mut text in gauges.iter_mut() {
+        text.0 = if boosting {
+            "BOOST".to_owned()
+        } else {
+            format!("Boost {:.0}%", car.boost * 100.)
+        };
+    }
+}
@@ -0,0 +1,202 @@
+use std::time::Duration;
+
+use avian2d::prelude::{Collider, LinearVelocity, Sensor, SpatialQuery, SpatialQueryFilter};
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_steam_p2p::{FilePath, NetworkIdentity, SteamP2PClient};
+
+use crate::{
+    car::{Car, CarInput, Fuel},
+    rng::{random_float, random_index, random_point_in_donut},
+};
+
+use super::{
+    car::vehicle_class::VehicleClass,
+    fuel::FuelCanister,
+    health::Dead,
+    recovery::Recovery,
+    world::{nearest_intersection, street_intersection},
+    zombies::Zombie,
+    Player,
+};
+
+const HUNT_RANGE: f32 = 300.;
+const WAYPOINT_RADIUS: f32 = 48.;
+const TURN_CHANCE: f32 = 0.3;
+/// Below this share of a full tank bots stop hunting and drive to the nearest fuel canister.
+const LOW_FUEL: f32 = 0.3;
+/// Canisters are kept around every car, bots included, so one is usually this close.
+const FUEL_SEARCH_RANGE: f32 = 1500.;
+/// Bots join at a street intersection this far from a random human car, out of sight but close
+/// enough to meet.
+const SPAWN_MIN_RANGE: f32 = 400.;
+const SPAWN_MAX_RANGE: f32 = 800.;
+
+pub struct ZOBotsPlugin;
+impl Plugin for ZOBotsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BotSettings>().add_systems(
+            Update,
+            (
+                spawn_bots.run_if(on_timer(Duration::from_secs(2))),
+                drive_bots,
+            ),
+        );
+    }
+}
+
+/// Number of cars the host keeps in the lobby by adding bots next to the human players.
+#[derive(Resource)]
+pub struct BotSettings {
+    pub cars: usize,
+}
+
+impl Default for BotSettings {
+    fn default() -> Self {
+        BotSettings { cars: 4 }
+    }
+}
+
+/// AI driver of a host-owned car. It writes the same `CarInput` the keyboard does for players.
+#[derive(Component, Default)]
+pub struct Bot {
+    waypoint: Option<IVec2>,
+}
+
+fn spawn_bots(
+    mut client: ResMut<SteamP2PClient>,
+    settings: Res<BotSettings>,
+    spatial: SpatialQuery,
+    sensors: Query<(), With<Sensor>>,
+    humans: Query<&Transform, (With<Car>, Without<Bot>, Without<Dead>)>,
+    bots: Query<(), (With<Bot>, Without<Dead>)>,
+) {
+    if !client.is_lobby_owner().is_ok_and(|owner| owner) {
+        return;
+    }
+    let human_positions: Vec<Vec2> = humans.iter().map(|t| t.translation.xy()).collect();
+    let bots = bots.iter().count();
+    if human_positions.is_empty() || human_positions.len() + bots >= settings.cars {
+        return;
+    }
+
+    let class = VehicleClass::ALL[random_index(VehicleClass::ALL.len())];
+    let near = human_positions[random_index(human_positions.len())];
+    let position = street_intersection(nearest_intersection(
+        near + random_point_in_donut(SPAWN_MIN_RANGE, SPAWN_MAX_RANGE),
+    ));
+    let spec = class.spec();
+    // Try again on the next tick rather than dropping a car onto another one.
+    let occupied = spatial
+        .shape_intersections(
+            &Collider::rectangle(spec.width, spec.length),
+            position,
+            0.,
+            &SpatialQueryFilter::default(),
+        )
+        .into_iter()
+        .any(|entity| !sensors.contains(entity));
+    if occupied {
+        return;
+    }
+    let _ = client.instantiate(
+        FilePath("Bot".to_owned()),
+        Some(vec![class.to_byte()]),
+        Transform::from_translation(position.extend(0.)),
+    );
+}
+
+fn drive_bots(
+    client: Res<SteamP2PClient>,
+    zombies: Query<&Transform, With<Zombie>>,
+    canisters: Query<&Transform, With<FuelCanister>>,
+    mut bots: Query<
+        (
+            &NetworkIdentity,
+            &Transform,
+            &LinearVelocity,
+            &Car,
+            &Fuel,
+            &Recovery,
+            &mut Bot,
+            &mut CarInput,
+        ),
+        With<Player>,
+    >,
+) {
+    for (identity, transform, velocity, car, fuel, recovery, mut bot, mut input) in bots.iter_mut()
+    {
+        if identity.id.owner != client.id {
+            continue;
+        }
+        let position = transform.translation.xy();
+        let heading = transform.up().xy();
+
+        let refuel = if fuel.fraction() < LOW_FUEL {
+            nearest_within(canisters.iter(), position, FUEL_SEARCH_RANGE)
+        } else {
+            None
+        };
+        let target = match refuel.or_else(|| nearest_within(zombies.iter(), position, HUNT_RANGE)) {
+            Some(target) => target,
+            None => {
+                let reached = bot.waypoint.is_none_or(|waypoint| {
+                    street_intersection(waypoint).distance(position) < WAYPOINT_RADIUS
+                });
+                if reached {
+                    bot.waypoint = Some(next_waypoint(position, heading));
+                }
+                street_intersection(bot.waypoint.unwrap())
+            }
+        };
+
+        // Positive angles are to the left of the car, like `CarInput::steer`.
+        let local = (transform.rotation.inverse() * (target - position).extend(0.)).xy();
+        let angle = (-local.x).atan2(local.y);
+        let speed = velocity.length();
+
+        input.steer = (angle * 2.).clamp(-1., 1.);
+        input.throttle = if angle.abs() > 1.2 && speed > 150. {
+            0.3
+        } else {
+            1.
+        };
+        input.drift = angle.abs() > 0.8 && speed > 200.;
+        input.boost = car.boost >= 1. && angle.abs() < 0.2;
+        input.reset = recovery.can_reset();
+    }
+}
+
+fn nearest_within<'a>(
+    transforms: impl Iterator<Item = &'a Transform>,
+    position: Vec2,
+    range: f32,
+) -> Option<Vec2> {
+    transforms
+        .map(|t| t.translation.xy())
+        .filter(|p| p.distance(position) < range)
+        .min_by(|a, b| {
+            a.distance_squared(position)
+                .total_cmp(&b.distance_squared(position))
+        })
+}
+
+/// Picks the next intersection along the street the car is facing, sometimes turning left or right.
+fn next_waypoint(position: Vec2, heading: Vec2) -> IVec2 {
+    let forward = if heading.x.abs() > heading.y.abs() {
+        IVec2::new(heading.x.signum() as i32, 0)
+    } else {
+        IVec2::new(0, heading.y.signum() as i32)
+    };
+    let direction = if random_float(0.0..1.) < TURN_CHANCE {
+        let left = IVec2::new(-forward.y, forward.x);
+        if random_float(0.0..1.) < 0.5 {
+            left
+        } else {
+            -left
+        }
+    } else {
+        forward
+    };
+
+    nearest_intersection(position) + direction
+}
@@ -1,29 +1,77 @@
-use avian2d::prelude::{Collider, Collision, ExternalForce, LinearVelocity, Mass, RigidBody};
-use bevy::prelude::*;
+pub mod vehicle_class;
+
+use std::time::Duration;
+
+use avian2d::prelude::{
+    Collider, Collision, ExternalForce, ExternalImpulse, LinearVelocity, Mass, RigidBody,
+};
+use bevy::{input::InputSystem, prelude::*, time::common_conditions::on_timer};
 use bevy_steam_p2p::{
-    networked_events::event::Networked, networked_transform::NetworkedTransform, NetworkIdentity,
-    SteamId,
+    networked_events::{event::Networked, register::NetworkedEvents},
+    networked_transform::NetworkedTransform,
+    NetworkId, NetworkIdentity, SteamId, SteamP2PClient,
 };
+use serde::{Deserialize, Serialize};
 
 use crate::{
     camera_follow::CameraFollow,
-    car::{tire::Tire, Car},
-    utils::{query_double, query_double_mut},
+    car::{steering::Steering, tire::Tire, Car, CarInput, Fuel},
+    particles::spawn_smoke,
+    rng::random_float,
+    utils::query_double,
 };
 
+use vehicle_class::VehicleClass;
+
 use super::{
-    health::{ChangeHealth, Health},
+    boost::Boosting,
+    bots::Bot,
+    garage::Upgrades,
+    health::{ChangeHealth, Dead, Health, Killed, Kills},
+    lobby::LobbySettings,
+    recovery::Recovery,
+    spawn_banner,
     zombies::Zombie,
-    Player,
+    LocalPlayer, Player,
 };
 
-const TIRE_GRIP: f32 = 0.7;
+const DRIFT_GRIP_FACTOR: f32 = 0.3;
+const CAR_HEALTH: u32 = 100;
+const IMPACT_DAMAGE_THRESHOLD: f32 = 60.;
+const IMPACT_DAMAGE_SCALE: f32 = 0.25;
+const SMOKE_HEALTH_FRACTION: f32 = 0.5;
+const BOOST_POWER_FACTOR: f32 = 1.8;
+const BOOST_PER_KILL: f32 = 0.1;
+const RAM_MIN_SPEED: f32 = 80.;
+const RAM_DAMAGE_SCALE: f32 = 0.2;
+const RAM_KNOCKBACK: f32 = 0.6;
 
 pub struct ZOCarPlugin;
 
 impl Plugin for ZOCarPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (turning, drift, handle_collisions));
+        app.add_networked_event::<RamImpact>()
+            .add_networked_event::<DriverInput>()
+            .add_systems(
+                PreUpdate,
+                (keyboard_input, handle_driver_input)
+                    .chain()
+                    .after(InputSystem),
+            )
+            .add_systems(
+                Update,
+                (
+                    drift,
+                    handle_collisions,
+                    boost_from_kills,
+                    handle_car_collisions,
+                    handle_ram_impact,
+                    impact_damage,
+                    update_power,
+                    damage_smoke.run_if(on_timer(Duration::from_millis(100))),
+                    handle_wreck,
+                ),
+            );
     }
 }
 
@@ -32,38 +80,59 @@ pub fn spawn_car(
     asset_server: &AssetServer,
     network_identity: NetworkIdentity,
     id: SteamId,
+    transform: Transform,
+    class: VehicleClass,
+    upgrades: Upgrades,
+    bot: bool,
 ) {
-    let width = 16.;
-    let length = 32.;
+    let spec = class.spec();
+    let power = spec.power * upgrades.power_factor();
 
     let car = commands
         .spawn((
             Player,
-            Car::new(4000., 4000.),
-            Transform::from_translation(Vec3::Z),
+            class,
+            upgrades,
+            Car::new(power, power),
+            Steering::new(spec.steering_rate, 0.35, 300.).with_self_centering(6.),
+            transform.with_translation(transform.translation.with_z(1.)),
             RigidBody::Dynamic,
-            Mass(1.),
+            Mass(spec.mass),
             ExternalForce::default().with_persistence(false),
-            Collider::rectangle(width, length),
-            Sprite::from_image(asset_server.load("sprites/car.png")),
+            ExternalImpulse::default().with_persistence(false),
+            Collider::rectangle(spec.width, spec.length),
+            Health::new(CAR_HEALTH + upgrades.bonus_health(), false),
+            Kills::default(),
+            Fuel::new(100., 0.8),
+            Recovery::new(),
+            Sprite {
+                image: asset_server.load("sprites/car.png"),
+                custom_size: Some(Vec2::new(spec.width, spec.length)),
+                color: spec.color,
+                ..default()
+            },
             network_identity.clone(),
             NetworkedTransform::new(true, true, false),
         ))
         .with_children(|children| {
-            for i in 0..4 {
-                let front = i < 2;
-                let x = width / 2. * if i % 2 == 0 { 1. } else { -1. };
-                let y = length / 2. * if front { 1. } else { -1. };
-
+            for tire in spec.tires.iter() {
                 children.spawn((
-                    Transform::from_translation(Vec3::new(x, y, 0.)),
-                    Tire::new(front, if front { Some(30.) } else { None }, 0.5, TIRE_GRIP),
+                    Transform::from_translation(tire.position.extend(0.)),
+                    Tire::new(
+                        tire.powered,
+                        tire.turning_radius,
+                        spec.rolling_resistance,
+                        spec.grip * upgrades.grip_factor(),
+                    ),
                 ));
             }
         })
         .id();
 
-    if network_identity.id.owner == id {
+    if bot {
+        commands.entity(car).insert(Bot::default());
+    } else if network_identity.id.owner == id {
+        commands.entity(car).insert(LocalPlayer);
         commands.spawn((
             Camera2d,
             Projection::from(OrthographicProjection {
@@ -75,52 +144,125 @@ pub fn spawn_car(
     }
 }
 
-fn turning(keys: Res<ButtonInput<KeyCode>>, mut tires: Query<(&mut Transform, &Tire)>) {
-    for (mut transform, tire) in tires.iter_mut() {
-        let Some(turning_radius) = tire.turning_radius else {
-            return;
-        };
-        let rads = turning_radius.to_radians();
-        let mut rotation = Quat::default();
+/// The player driving a car, which isn't always its owner. The owner keeps simulating the car and
+/// takes the controls of any other driver from `DriverInput`.
+#[derive(Component)]
+pub struct Driver(pub SteamId);
 
-        if keys.pressed(KeyCode::KeyA) {
-            rotation = Quat::from_rotation_z(rads)
-        } else if keys.pressed(KeyCode::KeyD) {
-            rotation = Quat::from_rotation_z(-rads)
-        }
+/// Sent every frame by a player driving a car owned by someone else.
+#[derive(Event, Serialize, Clone, Deserialize)]
+pub struct DriverInput {
+    car: NetworkId,
+    driver: SteamId,
+    throttle: f32,
+    steer: f32,
+    drift: bool,
+    boost: bool,
+    reset: bool,
+    horn: bool,
+}
+
+fn keyboard_input(
+    client: Res<SteamP2PClient>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut cars: Query<(&NetworkIdentity, &mut CarInput), (With<LocalPlayer>, With<Player>)>,
+    mut input_w: EventWriter<Networked<DriverInput>>,
+) {
+    let axis = |positive, negative| {
+        keys.pressed(positive) as i32 as f32 - keys.pressed(negative) as i32 as f32
+    };
 
-        transform.rotation = rotation;
+    for (identity, mut input) in cars.iter_mut() {
+        input.throttle = axis(KeyCode::KeyW, KeyCode::KeyS);
+        input.steer = axis(KeyCode::KeyA, KeyCode::KeyD);
+        input.drift = keys.pressed(KeyCode::ShiftLeft);
+        input.boost = keys.just_pressed(KeyCode::Space);
+        input.reset = keys.just_pressed(KeyCode::KeyR);
+        input.horn = keys.just_pressed(KeyCode::KeyH);
+
+        if identity.id.owner != client.id {
+            input_w.send(Networked::new(DriverInput {
+                car: identity.id.clone(),
+                driver: client.id,
+                throttle: input.throttle,
+                steer: input.steer,
+                drift: input.drift,
+                boost: input.boost,
+                reset: input.reset,
+                horn: input.horn,
+            }));
+        }
     }
 }
 
-fn drift(keys: Res<ButtonInput<KeyCode>>, mut tires: Query<&mut Tire>) {
-    for mut tire in tires.iter_mut() {
-        if keys.pressed(KeyCode::ShiftLeft) {
-            tire.grip = 0.2
-        } else {
-            tire.grip = TIRE_GRIP;
+/// Applies the controls of cars this peer owns but someone else drives. Presses only last the
+/// frame they arrive in, and any press among several inputs arriving at once counts.
+fn handle_driver_input(
+    client: Res<SteamP2PClient>,
+    mut input_r: EventReader<DriverInput>,
+    mut cars: Query<(&NetworkIdentity, &Driver, &mut CarInput)>,
+) {
+    for (identity, driver, mut input) in cars.iter_mut() {
+        if identity.id.owner == client.id && driver.0 != client.id {
+            input.boost = false;
+            input.reset = false;
+            input.horn = false;
+        }
+    }
+    for received in input_r.read() {
+        if received.car.owner != client.id {
+            continue;
         }
+        let Some((_, _, mut input)) = cars
+            .iter_mut()
+            .find(|(i, driver, _)| i.id == received.car && driver.0 == received.driver)
+        else {
+            continue;
+        };
+        input.throttle = received.throttle;
+        input.steer = received.steer;
+        input.drift = received.drift;
+        input.boost |= received.boost;
+        input.reset |= received.reset;
+        input.horn |= received.horn;
     }
 }
 
+fn drift(cars: Query<(&Health, &CarInput), With<Car>>, mut tires: Query<(&Parent, &mut Tire)>) {
+    for (car_entity, mut tire) in tires.iter_mut() {
+        let Ok((health, input)) = cars.get(**car_entity) else {
+            continue;
+        };
+        let drift_factor = if input.drift { DRIFT_GRIP_FACTOR } else { 1. };
+        tire.grip = tire.base_grip * drift_factor * damage_factor(health);
+    }
+}
+
+/// How much of its engine power and grip a damaged car keeps.
+fn damage_factor(health: &Health) -> f32 {
+    0.5 + 0.5 * health.fraction()
+}
+
+/// Only the car's owner reports a ram, so each hit deals its damage once.
 fn handle_collisions(
+    client: Res<SteamP2PClient>,
     mut collision_event_reader: EventReader<Collision>,
-    mut cars: Query<(Entity, &Transform, &LinearVelocity), With<Car>>,
-    mut zombies: Query<(&NetworkIdentity, &Transform, &mut Health), With<Zombie>>,
+    cars: Query<(&NetworkIdentity, &Upgrades, &Transform, &LinearVelocity)>,
+    zombies: Query<(&NetworkIdentity, &Transform), With<Zombie>>,
     mut change_health_w: EventWriter<Networked<ChangeHealth>>,
 ) {
     let minimum_velocity = 100.;
 
     for Collision(contacts) in collision_event_reader.read() {
         let Some((
-            (car, car_transform, car_velocity),
-            (zombie, zombie_transform, mut zombie_health),
-        )) = query_double_mut(&mut cars, &mut zombies, contacts.entity1, contacts.entity2)
+            (car_identity, upgrades, car_transform, car_velocity),
+            (zombie, zombie_transform),
+        )) = query_double(&cars, &zombies, contacts.entity1, contacts.entity2)
         else {
             continue;
         };
-        if !contacts.collision_started() {
-            return;
+        if !contacts.collision_started() || car_identity.id.owner != client.id {
+            continue;
         }
         let force_dir = (zombie_transform.translation - car_transform.translation)
             .normalize()
@@ -129,8 +271,199 @@ fn handle_collisions(
         if shared_velocity > minimum_velocity {
             change_health_w.send(Networked::new(ChangeHealth {
                 network_id: zombie.id.clone(),
-                change: -100,
+                change: -(100. * upgrades.ram_damage_factor()) as i32,
+                source: Some(car_identity.id.clone()),
             }));
         }
     }
 }
+
+/// Boost fills up from zombies the car is credited with killing.
+fn boost_from_kills(
+    mut killed_r: EventReader<Killed>,
+    zombies: Query<(), With<Zombie>>,
+    mut cars: Query<(&NetworkIdentity, &mut Car)>,
+) {
+    for killed in killed_r.read() {
+        let Some(killer) = &killed.killer else {
+            continue;
+        };
+        if !zombies.contains(killed.victim) {
+            continue;
+        }
+        if let Some((_, mut car)) = cars.iter_mut().find(|(i, _)| i.id == *killer) {
+            car.add_boost(BOOST_PER_KILL);
+        }
+    }
+}
+
+fn impact_damage(
+    client: Res<SteamP2PClient>,
+    mut collision_event_reader: EventReader<Collision>,
+    cars: Query<(&NetworkIdentity, &Upgrades), With<Car>>,
+    zombies: Query<(), With<Zombie>>,
+    mut change_health_w: EventWriter<Networked<ChangeHealth>>,
+) {
+    for Collision(contacts) in collision_event_reader.read() {
+        if !contacts.collision_started() {
+            continue;
+        }
+        // Zombies hurt cars through their melee attacks, not by bumping into them.
+        if zombies.contains(contacts.entity1) || zombies.contains(contacts.entity2) {
+            continue;
+        }
+        // Rams between cars are damaged by `handle_car_collisions` alone.
+        if cars.contains(contacts.entity1) && cars.contains(contacts.entity2) {
+            continue;
+        }
+        let damage =
+            (contacts.total_normal_impulse - IMPACT_DAMAGE_THRESHOLD) * IMPACT_DAMAGE_SCALE;
+        if damage <= 0. {
+            continue;
+        }
+        for entity in [contacts.entity1, contacts.entity2] {
+            let Ok((identity, upgrades)) = cars.get(entity) else {
+                continue;
+            };
+            if identity.id.owner != client.id {
+                continue;
+            }
+            change_health_w.send(Networked::new(ChangeHealth {
+                network_id: identity.id.clone(),
+                change: -(damage * upgrades.self_damage_factor()) as i32,
+                source: None,
+            }));
+        }
+    }
+}
+
+/// Sent by the owner of a car that rams another car. The victim's owner applies the knockback.
+#[derive(Event, Serialize, Clone, Deserialize)]
+pub struct RamImpact {
+    attacker: NetworkId,
+    victim: NetworkId,
+    impulse: [f32; 2],
+}
+
+fn handle_car_collisions(
+    client: Res<SteamP2PClient>,
+    settings: Res<LobbySettings>,
+    mut collision_event_reader: EventReader<Collision>,
+    cars: Query<
+        (
+            &NetworkIdentity,
+            &Transform,
+            &LinearVelocity,
+            &Mass,
+            &Upgrades,
+        ),
+        With<Car>,
+    >,
+    mut change_health_w: EventWriter<Networked<ChangeHealth>>,
+    mut ram_w: EventWriter<Networked<RamImpact>>,
+) {
+    for Collision(contacts) in collision_event_reader.read() {
+        if !contacts.collision_started() {
+            continue;
+        }
+        let Ok([first, second]) = cars.get_many([contacts.entity1, contacts.entity2]) else {
+            continue;
+        };
+
+        // Each peer only judges the rams of the cars it simulates.
+        for (attacker, victim) in [(first, second), (second, first)] {
+            let (attacker_identity, attacker_transform, attacker_velocity, attacker_mass, upgrades) =
+                attacker;
+            let (victim_identity, victim_transform, _, _, _) = victim;
+            if attacker_identity.id.owner != client.id {
+                continue;
+            }
+            let direction = (victim_transform.translation - attacker_transform.translation)
+                .xy()
+                .normalize_or_zero();
+            let closing_speed = attacker_velocity.dot(direction);
+            if closing_speed < RAM_MIN_SPEED {
+                continue;
+            }
+
+            let impulse = direction * closing_speed * attacker_mass.0 * RAM_KNOCKBACK;
+            ram_w.send(Networked::new(RamImpact {
+                attacker: attacker_identity.id.clone(),
+                victim: victim_identity.id.clone(),
+                impulse: impulse.to_array(),
+            }));
+            if settings.pvp_damage {
+                change_health_w.send(Networked::new(ChangeHealth {
+                    network_id: victim_identity.id.clone(),
+                    change: -((closing_speed - RAM_MIN_SPEED)
+                        * RAM_DAMAGE_SCALE
+                        * upgrades.ram_damage_factor()) as i32,
+                    source: Some(attacker_identity.id.clone()),
+                }));
+            }
+        }
+    }
+}
+
+fn handle_ram_impact(
+    client: Res<SteamP2PClient>,
+    mut ram_r: EventReader<RamImpact>,
+    mut cars: Query<(&NetworkIdentity, &mut ExternalImpulse), With<Car>>,
+) {
+    for ram in ram_r.read() {
+        if ram.victim.owner != client.id {
+            continue;
+        }
+        let Some((_, mut impulse)) = cars.iter_mut().find(|(i, _)| i.id == ram.victim) else {
+            continue;
+        };
+        impulse.apply_impulse(Vec2::from_array(ram.impulse));
+    }
+}
+
+fn update_power(mut cars: Query<(&mut Car, &Health, Has<Boosting>), Without<Dead>>) {
+    for (mut car, health, boosting) in cars.iter_mut() {
+        let boost = if boosting { BOOST_POWER_FACTOR } else { 1. };
+        car.current_power = car.max_power * damage_factor(health) * boost;
+    }
+}
+
+fn damage_smoke(mut commands: Commands, cars: Query<(&GlobalTransform, &Health), With<Car>>) {
+    for (transform, health) in cars.iter() {
+        let fraction = health.fraction();
+        if fraction > SMOKE_HEALTH_FRACTION || random_float(0.0..SMOKE_HEALTH_FRACTION) < fraction {
+            continue;
+        }
+        spawn_smoke(
+            &mut commands,
+            transform.translation().xy(),
+            Color::srgba(0.2, 0.2, 0.2, 0.6),
+            6.,
+        );
+    }
+}
+
+fn handle_wreck(
+    mut commands: Commands,
+    mut cars: Query<
+        (
+            Entity,
+            &mut Car,
+            &mut CarInput,
+            &mut Sprite,
+            Has<LocalPlayer>,
+        ),
+        Added<Dead>,
+    >,
+) {
+    for (entity, mut car, mut input, mut sprite, local) in cars.iter_mut() {
+        car.current_power = 0.;
+        *input = CarInput::default();
+        sprite.color = Color::srgb(0.3, 0.3, 0.3);
+        commands.entity(entity).remove::<Player>();
+
+        if local {
+            spawn_banner(&mut commands, "WRECKED");
+        }
+    }
+}
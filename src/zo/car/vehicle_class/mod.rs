@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+
+/// Vehicle a player picks in the lobby. Sent as the first byte of the "Player" instantiation
+/// payload so every peer builds the same car.
+#[derive(Component, Resource, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum VehicleClass {
+    #[default]
+    Car,
+    Truck,
+    Buggy,
+    Motorbike,
+}
+
+pub struct VehicleSpec {
+    pub width: f32,
+    pub length: f32,
+    pub mass: f32,
+    /// Engine power of each powered tire.
+    pub power: f32,
+    pub grip: f32,
+    pub rolling_resistance: f32,
+    pub steering_rate: f32,
+    pub color: Color,
+    pub tires: Vec<TireSpec>,
+}
+
+pub struct TireSpec {
+    pub position: Vec2,
+    pub powered: bool,
+    pub turning_radius: Option<f32>,
+}
+
+impl VehicleClass {
+    pub const ALL: [VehicleClass; 4] = [
+        VehicleClass::Car,
+        VehicleClass::Truck,
+        VehicleClass::Buggy,
+        VehicleClass::Motorbike,
+    ];
+
+    pub fn to_byte(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_byte(byte: u8) -> Option<VehicleClass> {
+        VehicleClass::ALL.get(byte as usize).copied()
+    }
+
+    pub fn from_payload(payload: &[u8]) -> VehicleClass {
+        payload
+            .first()
+            .and_then(|byte| VehicleClass::from_byte(*byte))
+            .unwrap_or_default()
+    }
+
+    pub fn spec(self) -> VehicleSpec {
+        match self {
+            VehicleClass::Car => VehicleSpec {
+                width: 16.,
+                length: 32.,
+                mass: 1.,
+                power: 67.,
+                grip: 0.7,
+                rolling_resistance: 0.008,
+                steering_rate: 4.,
+                color: Color::WHITE,
+                tires: axles(16., &[(16., true, Some(30.)), (-16., false, None)]),
+            },
+            VehicleClass::Truck => VehicleSpec {
+                width: 22.,
+                length: 48.,
+                mass: 2.5,
+                power: 67.,
+                grip: 1.5,
+                rolling_resistance: 0.016,
+                steering_rate: 2.5,
+                color: Color::srgb(0.6, 0.7, 1.),
+                tires: axles(
+                    22.,
+                    &[
+                        (24., false, Some(22.)),
+                        (-8., true, None),
+                        (-24., true, None),
+                    ],
+                ),
+            },
+            VehicleClass::Buggy => VehicleSpec {
+                width: 14.,
+                length: 26.,
+                mass: 0.7,
+                power: 37.,
+                grip: 0.55,
+                rolling_resistance: 0.007,
+                steering_rate: 5.,
+                color: Color::srgb(1., 0.85, 0.4),
+                tires: axles(14., &[(13., true, Some(35.)), (-13., true, None)]),
+            },
+            VehicleClass::Motorbike => VehicleSpec {
+                width: 6.,
+                length: 20.,
+                mass: 0.4,
+                power: 58.,
+                grip: 0.6,
+                rolling_resistance: 0.005,
+                steering_rate: 6.,
+                color: Color::srgb(1., 0.5, 0.5),
+                tires: vec![
+                    TireSpec {
+                        position: Vec2::new(0., 10.),
+                        powered: false,
+                        turning_radius: Some(25.),
+                    },
+                    TireSpec {
+                        position: Vec2::new(0., -10.),
+                        powered: true,
+                        turning_radius: None,
+                    },
+                ],
+            },
+        }
+    }
+}
+
+/// Builds a left and right tire for every `(y, powered, turning_radius)` axle.
+fn axles(width: f32, axles: &[(f32, bool, Option<f32>)]) -> Vec<TireSpec> {
+    axles
+        .iter()
+        .flat_map(|&(y, powered, turning_radius)| {
+            [1., -1.].map(|side| TireSpec {
+                position: Vec2::new(width / 2. * side, y),
+                powered,
+                turning_radius,
+            })
+        })
+        .collect()
+}
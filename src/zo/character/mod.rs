@@ -0,0 +1,330 @@
+use avian2d::prelude::{
+    Collider, LinearVelocity, LockedAxes, Mass, RigidBody, Sensor, SpatialQuery, SpatialQueryFilter,
+};
+use bevy::prelude::*;
+use bevy_steam_p2p::{
+    networked_events::{event::Networked, register::NetworkedEvents},
+    networked_transform::NetworkedTransform,
+    NetworkId, NetworkIdentity, SteamId,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    camera_follow::CameraFollow,
+    car::{Car, CarInput},
+};
+
+use super::{
+    bots::Bot,
+    car::Driver,
+    health::{Dead, Health},
+    spawn_banner, LocalPlayer, Player,
+};
+
+const CHARACTER_HEALTH: u32 = 30;
+const CHARACTER_SPEED: f32 = 80.;
+const CHARACTER_RADIUS: f32 = 3.;
+const ENTER_RANGE: f32 = 30.;
+/// Distance from the car's center to where the character gets out.
+const EXIT_DISTANCE: f32 = 16.;
+
+pub struct ZOCharacterPlugin;
+impl Plugin for ZOCharacterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_networked_event::<VehicleSwitch>().add_systems(
+            Update,
+            (
+                board_starting_vehicle,
+                character_movement,
+                follow_vehicle,
+                toggle_vehicle,
+                handle_vehicle_switch,
+                handle_character_death,
+            ),
+        );
+    }
+}
+
+/// A player's body. It rides inside `vehicle` or walks around on its own when that is `None`, and
+/// carries the `Player` marker only while on foot so zombies go after it instead of the empty car.
+#[derive(Component)]
+pub struct Character {
+    pub vehicle: Option<Entity>,
+}
+
+/// The character controlled by this peer's keyboard.
+#[derive(Component)]
+pub struct LocalCharacter;
+
+/// Waits for the owner's car to be instantiated before getting in.
+#[derive(Component)]
+struct AwaitingVehicle;
+
+/// Sent by a character's owner to get in (`Some`) or out (`None`) of a car. The entering player
+/// becomes its `Driver` and steers it with their keyboard, while the car's owner keeps simulating
+/// it so its network id never changes.
+#[derive(Event, Serialize, Clone, Deserialize)]
+pub struct VehicleSwitch {
+    character: NetworkId,
+    vehicle: Option<NetworkId>,
+    /// Where the character lands when getting out, picked by its owner clear of obstacles.
+    exit: [f32; 2],
+}
+
+pub fn spawn_character(
+    transform: Transform,
+    commands: &mut Commands,
+    network_identity: NetworkIdentity,
+    id: SteamId,
+) {
+    let character = commands
+        .spawn((
+            Character { vehicle: None },
+            AwaitingVehicle,
+            transform.with_translation(transform.translation.with_z(1.)),
+            Health::new(CHARACTER_HEALTH, false),
+            Sprite::from_color(
+                Color::srgb(0.3, 0.5, 1.),
+                Vec2::splat(CHARACTER_RADIUS * 2.),
+            ),
+            network_identity.clone(),
+            NetworkedTransform::new(true, true, false),
+        ))
+        .id();
+
+    if network_identity.id.owner == id {
+        commands.entity(character).insert(LocalCharacter);
+    }
+}
+
+/// Characters start inside the car their owner instantiated right before them.
+fn board_starting_vehicle(
+    mut commands: Commands,
+    mut characters: Query<(Entity, &NetworkIdentity, &mut Character), With<AwaitingVehicle>>,
+    cars: Query<(Entity, &NetworkIdentity), (With<Car>, Without<Bot>, Without<Dead>)>,
+) {
+    for (entity, identity, mut character) in characters.iter_mut() {
+        let Some((car, _)) = cars.iter().find(|(_, i)| i.id.owner == identity.id.owner) else {
+            continue;
+        };
+        character.vehicle = Some(car);
+        commands
+            .entity(entity)
+            .remove::<AwaitingVehicle>()
+            .insert(Visibility::Hidden);
+    }
+}
+
+fn character_movement(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut characters: Query<
+        (&mut LinearVelocity, &mut Transform),
+        (With<LocalCharacter>, With<Player>, Without<Dead>),
+    >,
+) {
+    let axis = |positive, negative| {
+        keys.pressed(positive) as i32 as f32 - keys.pressed(negative) as i32 as f32
+    };
+    let direction = Vec2::new(
+        axis(KeyCode::KeyD, KeyCode::KeyA),
+        axis(KeyCode::KeyW, KeyCode::KeyS),
+    )
+    .normalize_or_zero();
+
+    for (mut velocity, mut transform) in characters.iter_mut() {
+        **velocity = direction * CHARACTER_SPEED;
+        if direction != Vec2::ZERO {
+            transform.rotation = Quat::from_rotation_arc_2d(Vec2::Y, direction);
+        }
+    }
+}
+
+fn follow_vehicle(
+    mut characters: Query<(&Character, &mut Transform), Without<Car>>,
+    cars: Query<&Transform, With<Car>>,
+) {
+    for (character, mut transform) in characters.iter_mut() {
+        let Some(car_transform) = character.vehicle.and_then(|car| cars.get(car).ok()) else {
+            continue;
+        };
+        transform.translation = car_transform.translation;
+        transform.rotation = car_transform.rotation;
+    }
+}
+
+/// Any free car nearby can be entered, including abandoned ones and bots.
+fn toggle_vehicle(
+    keys: Res<ButtonInput<KeyCode>>,
+    spatial: SpatialQuery,
+    sensors: Query<(), With<Sensor>>,
+    characters: Query<
+        (&NetworkIdentity, &Character, &Transform),
+        (With<LocalCharacter>, Without<Dead>),
+    >,
+    free_cars: Query<
+        (&NetworkIdentity, &Transform),
+        (With<Car>, Or<(Without<Player>, With<Bot>)>, Without<Dead>),
+    >,
+    cars: Query<&Transform, With<Car>>,
+    mut switch_w: EventWriter<Networked<VehicleSwitch>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyF) {
+        return;
+    }
+    for (identity, character, transform) in characters.iter() {
+        let (vehicle, exit) = match character.vehicle {
+            Some(car) => {
+                let Ok(car_transform) = cars.get(car) else {
+                    continue;
+                };
+                let filter = SpatialQueryFilter::from_excluded_entities([car]);
+                let Some(exit) = exit_points(car_transform).into_iter().find(|point| {
+                    spatial
+                        .shape_intersections(
+                            &Collider::circle(CHARACTER_RADIUS),
+                            *point,
+                            0.,
+                            &filter,
+                        )
+                        .into_iter()
+                        .all(|entity| sensors.contains(entity))
+                }) else {
+                    continue;
+                };
+                (None, exit)
+            }
+            None => {
+                let Some((car, _)) = free_cars
+                    .iter()
+                    .find(|(_, t)| t.translation.distance(transform.translation) < ENTER_RANGE)
+                else {
+                    continue;
+                };
+                (Some(car.id.clone()), transform.translation.xy())
+            }
+        };
+        switch_w.send(Networked::new(VehicleSwitch {
+            character: identity.id.clone(),
+            vehicle,
+            exit: exit.to_array(),
+        }));
+    }
+}
+
+/// Spots around a car to get out at, in order of preference: the driver's side, the other side,
+/// behind and in front.
+fn exit_points(car: &Transform) -> [Vec2; 4] {
+    let position = car.translation.xy();
+    let right = car.right().xy();
+    let up = car.up().xy();
+    [
+        position - right * EXIT_DISTANCE,
+        position + right * EXIT_DISTANCE,
+        position - up * EXIT_DISTANCE * 2.,
+        position + up * EXIT_DISTANCE * 2.,
+    ]
+}
+
+fn handle_vehicle_switch(
+    mut commands: Commands,
+    mut switch_r: EventReader<VehicleSwitch>,
+    mut characters: Query<(
+        Entity,
+        &NetworkIdentity,
+        &mut Character,
+        &mut Transform,
+        Has<LocalCharacter>,
+    )>,
+    mut cars: Query<
+        (Entity, &NetworkIdentity, &Transform, &mut CarInput),
+        (With<Car>, Without<Character>),
+    >,
+    local_cars: Query<Entity, With<LocalPlayer>>,
+    mut cameras: Query<&mut CameraFollow>,
+) {
+    for switch in switch_r.read() {
+        let Some((entity, _, mut character, mut transform, local)) = characters
+            .iter_mut()
+            .find(|(_, i, _, _, _)| i.id == switch.character)
+        else {
+            continue;
+        };
+
+        let follow = match &switch.vehicle {
+            Some(vehicle) => {
+                let Some((car, _, _, mut input)) =
+                    cars.iter_mut().find(|(_, i, _, _)| i.id == *vehicle)
+                else {
+                    continue;
+                };
+                character.vehicle = Some(car);
+                *input = CarInput::default();
+                commands
+                    .entity(entity)
+                    .remove::<(Player, RigidBody, Collider)>()
+                    .insert(Visibility::Hidden);
+                commands
+                    .entity(car)
+                    .insert((Player, Driver(switch.character.owner)))
+                    .remove::<Bot>();
+                if local {
+                    for other in local_cars.iter() {
+                        commands.entity(other).remove::<LocalPlayer>();
+                    }
+                    commands.entity(car).insert(LocalPlayer);
+                } else {
+                    commands.entity(car).remove::<LocalPlayer>();
+                }
+                car
+            }
+            None => {
+                let Some((car, _, _, mut input)) =
+                    character.vehicle.and_then(|car| cars.get_mut(car).ok())
+                else {
+                    continue;
+                };
+                character.vehicle = None;
+                *input = CarInput::default();
+                transform.translation =
+                    Vec2::from_array(switch.exit).extend(transform.translation.z);
+                commands.entity(entity).insert((
+                    Player,
+                    RigidBody::Dynamic,
+                    Mass(0.2),
+                    LockedAxes::ROTATION_LOCKED,
+                    Collider::circle(CHARACTER_RADIUS),
+                    Visibility::Inherited,
+                ));
+                commands.entity(car).remove::<(Player, Driver)>();
+                entity
+            }
+        };
+
+        if local {
+            for mut camera in cameras.iter_mut() {
+                camera.set_follow(follow);
+            }
+        }
+    }
+}
+
+/// A character dies with the car it is in, and on foot shows its own banner.
+fn handle_character_death(
+    mut commands: Commands,
+    wrecked: Query<(), (With<Car>, Added<Dead>)>,
+    characters: Query<(Entity, &Character, Has<Dead>, Has<LocalCharacter>)>,
+    died: Query<(), (With<Character>, Added<Dead>)>,
+) {
+    for (entity, character, dead, local) in characters.iter() {
+        if character.vehicle.is_some_and(|car| wrecked.contains(car)) && !dead {
+            commands.entity(entity).insert(Dead);
+            continue;
+        }
+        if died.contains(entity) {
+            commands.entity(entity).remove::<Player>();
+            if local && character.vehicle.is_none() {
+                spawn_banner(&mut commands, "YOU DIED");
+            }
+        }
+    }
+}
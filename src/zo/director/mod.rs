@@ -0,0 +1,310 @@
+use std::{fs, time::Duration};
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_steam_p2p::{networked_events::event::Networked, NetworkIdentity, SteamP2PClient};
+
+use super::{
+    health::{ChangeHealth, Health, Killed},
+    noise::Noise,
+    zombies::{archetype::ZombieArchetype, Zombie},
+    Player,
+};
+
+const CONFIG_PATH: &str = "director.txt";
+/// Radius of the noise that calls a horde onto the players, reaching past the spawn ring.
+const HORDE_CALL_RADIUS: f32 = 900.;
+
+pub struct ZODirectorPlugin;
+impl Plugin for ZODirectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DirectorConfig::load())
+            .init_resource::<Director>()
+            .add_systems(
+                Update,
+                (
+                    track_intensity,
+                    advance_phase,
+                    call_horde.run_if(on_timer(Duration::from_secs(5))),
+                ),
+            );
+    }
+}
+
+/// Tuning for the `Director`, read from `CONFIG_PATH` as `key=value` lines. Missing keys keep
+/// their defaults.
+#[derive(Resource)]
+pub struct DirectorConfig {
+    /// Multiplies every zombie count.
+    pub difficulty: f32,
+    /// Zombies kept around each player in each phase.
+    pub quiet_zombies: f32,
+    pub build_zombies: f32,
+    pub horde_zombies: f32,
+    /// Extra share of zombies per player for every player past the first.
+    pub player_scale: f32,
+    pub quiet_seconds: f32,
+    /// Longest a build-up lasts before the horde arrives anyway.
+    pub build_seconds: f32,
+    pub horde_seconds: f32,
+    /// A build-up turns into a horde early once players have gone this long without a fight.
+    pub calm_seconds: f32,
+    /// Intensity at which a horde backs off into a quiet phase.
+    pub peak_intensity: f32,
+    pub intensity_per_kill: f32,
+    pub intensity_per_damage: f32,
+    /// Intensity added while the most hurt player is down to no health, scaled by how much they
+    /// are missing.
+    pub intensity_per_missing_health: f32,
+    /// Intensity lost per second.
+    pub intensity_decay: f32,
+    /// Seconds between special zombies outside quiet phases.
+    pub special_seconds: f32,
+    pub horde_spawns_per_tick: f32,
+}
+
+impl Default for DirectorConfig {
+    fn default() -> Self {
+        DirectorConfig {
+            difficulty: 1.,
+            quiet_zombies: 15.,
+            build_zombies: 35.,
+            horde_zombies: 70.,
+            player_scale: 0.1,
+            quiet_seconds: 20.,
+            build_seconds: 60.,
+            horde_seconds: 30.,
+            calm_seconds: 25.,
+            peak_intensity: 1.,
+            intensity_per_kill: 0.04,
+            intensity_per_damage: 0.01,
+            intensity_per_missing_health: 0.5,
+            intensity_decay: 0.03,
+            special_seconds: 15.,
+            horde_spawns_per_tick: 3.,
+        }
+    }
+}
+
+impl DirectorConfig {
+    fn load() -> DirectorConfig {
+        fs::read_to_string(CONFIG_PATH)
+            .map(|text| DirectorConfig::parse(&text))
+            .unwrap_or_default()
+    }
+
+    fn parse(text: &str) -> DirectorConfig {
+        let mut config = DirectorConfig::default();
+        for line in text.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let Ok(value) = value.trim().parse::<f32>() else {
+                continue;
+            };
+            let field = match key.trim() {
+                "difficulty" => &mut config.difficulty,
+                "quiet_zombies" => &mut config.quiet_zombies,
+                "build_zombies" => &mut config.build_zombies,
+                "horde_zombies" => &mut config.horde_zombies,
+                "player_scale" => &mut config.player_scale,
+                "quiet_seconds" => &mut config.quiet_seconds,
+                "build_seconds" => &mut config.build_seconds,
+                "horde_seconds" => &mut config.horde_seconds,
+                "calm_seconds" => &mut config.calm_seconds,
+                "peak_intensity" => &mut config.peak_intensity,
+                "intensity_per_kill" => &mut config.intensity_per_kill,
+                "intensity_per_damage" => &mut config.intensity_per_damage,
+                "intensity_per_missing_health" => &mut config.intensity_per_missing_health,
+                "intensity_decay" => &mut config.intensity_decay,
+                "special_seconds" => &mut config.special_seconds,
+                "horde_spawns_per_tick" => &mut config.horde_spawns_per_tick,
+                _ => {
+                    warn!("Unknown director setting {key}");
+                    continue;
+                }
+            };
+            *field = value;
+        }
+        config
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Phase {
+    #[default]
+    Quiet,
+    Build,
+    Horde,
+}
+
+/// Paces zombie pressure on the host: quiet phases to recover, a build-up, then a horde that
+/// lasts until it runs out or the players are overwhelmed.
+#[derive(Resource, Default)]
+pub struct Director {
+    pub phase: Phase,
+    phase_time: f32,
+    /// How hard the players are being pushed: `pressure` plus how hurt the players are.
+    pub intensity: f32,
+    /// Raised by kills and damage taken, and wears off over time.
+    pressure: f32,
+    since_fight: f32,
+    since_special: f32,
+}
+
+impl Director {
+    /// Zombies to keep around each player.
+    pub fn zombie_cap(&self, config: &DirectorConfig, players: usize) -> usize {
+        let base = match self.phase {
+            Phase::Quiet => config.quiet_zombies,
+            Phase::Build => config.build_zombies,
+            Phase::Horde => config.horde_zombies,
+        };
+        let scale = 1. + players.saturating_sub(1) as f32 * config.player_scale;
+        (base * config.difficulty * scale).round() as usize
+    }
+
+    pub fn spawns_per_tick(&self, config: &DirectorConfig) -> usize {
+        match self.phase {
+            Phase::Horde => config.horde_spawns_per_tick.max(1.) as usize,
+            _ => 1,
+        }
+    }
+
+    /// Picks the next zombie to spawn, slipping in a special one when it's due.
+    pub fn next_archetype(&self, config: &DirectorConfig) -> ZombieArchetype {
+        if self.phase != Phase::Quiet && self.since_special > config.special_seconds {
+            return ZombieArchetype::random_special();
+        }
+        ZombieArchetype::random_common()
+    }
+
+    /// Called once `archetype` was actually spawned, so a special that didn't fit is retried.
+    pub fn spawned(&mut self, archetype: ZombieArchetype) {
+        if archetype.is_special() {
+            self.since_special = 0.;
+        }
+    }
+
+    fn enter(&mut self, phase: Phase) {
+        debug!("Director: {:?} (intensity {:.2})", phase, self.intensity);
+        self.phase = phase;
+        self.phase_time = 0.;
+        if phase == Phase::Build {
+            // Fights from the last horde shouldn't count towards the calm that ends this build-up.
+            self.since_fight = 0.;
+        }
+    }
+}
+
+fn track_intensity(
+    time: Res<Time>,
+    config: Res<DirectorConfig>,
+    mut director: ResMut<Director>,
+    mut killed_r: EventReader<Killed>,
+    mut change_r: EventReader<ChangeHealth>,
+    zombies: Query<(), With<Zombie>>,
+    players: Query<(&NetworkIdentity, &Health), With<Player>>,
+) {
+    let dt = time.delta_secs();
+    director.since_fight += dt;
+    director.since_special += dt;
+    director.pressure = (director.pressure - config.intensity_decay * dt).max(0.);
+
+    for killed in killed_r.read() {
+        if zombies.contains(killed.victim) {
+            director.pressure += config.intensity_per_kill;
+            director.since_fight = 0.;
+        }
+    }
+    for change in change_r.read() {
+        if change.change >= 0 || !players.iter().any(|(i, _)| i.id == change.network_id) {
+            continue;
+        }
+        director.pressure += -change.change as f32 * config.intensity_per_damage;
+        director.since_fight = 0.;
+    }
+
+    let missing_health = players
+        .iter()
+        .map(|(_, health)| 1. - health.fraction())
+        .fold(0., f32::max);
+    director.intensity = director.pressure + missing_health * config.intensity_per_missing_health;
+}
+
+fn advance_phase(time: Res<Time>, config: Res<DirectorConfig>, mut director: ResMut<Director>) {
+    director.phase_time += time.delta_secs();
+    let next = match director.phase {
+        Phase::Quiet if director.phase_time > config.quiet_seconds => Some(Phase::Build),
+        Phase::Build
+            if director.phase_time > config.build_seconds
+                || director.since_fight > config.calm_seconds =>
+        {
+            Some(Phase::Horde)
+        }
+        Phase::Horde
+            if director.phase_time > config.horde_seconds
+                || director.intensity > config.peak_intensity =>
+        {
+            Some(Phase::Quiet)
+        }
+        _ => None,
+    };
+    if let Some(next) = next {
+        director.enter(next);
+    }
+}
+
+/// During a horde the host keeps making noise at every player so the whole wave closes in.
+fn call_horde(
+    client: Res<SteamP2PClient>,
+    director: Res<Director>,
+    players: Query<&Transform, With<Player>>,
+    mut noise_w: EventWriter<Networked<Noise>>,
+) {
+    if director.phase != Phase::Horde || !client.is_lobby_owner().is_ok_and(|owner| owner) {
+        return;
+    }
+    for transform in players.iter() {
+        noise_w.send(Networked::new(Noise {
+            position: transform.translation.xy().to_array(),
+            radius: HORDE_CALL_RADIUS,
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_known_keys() {
+        let config =
+            DirectorConfig::parse("difficulty=2\nhorde_zombies = 90\n  calm_seconds= 5.5  ");
+        assert_eq!(config.difficulty, 2.);
+        assert_eq!(config.horde_zombies, 90.);
+        assert_eq!(config.calm_seconds, 5.5);
+    }
+
+    #[test]
+    fn parse_keeps_defaults_for_missing_keys() {
+        let config = DirectorConfig::parse("difficulty=3");
+        let default = DirectorConfig::default();
+        assert_eq!(config.quiet_zombies, default.quiet_zombies);
+        assert_eq!(config.special_seconds, default.special_seconds);
+    }
+
+    #[test]
+    fn parse_skips_bad_lines() {
+        let config =
+            DirectorConfig::parse("# comment\ndifficulty\nquiet_zombies=many\nspeed=4\n\n");
+        let default = DirectorConfig::default();
+        assert_eq!(config.difficulty, default.difficulty);
+        assert_eq!(config.quiet_zombies, default.quiet_zombies);
+    }
+
+    #[test]
+    fn parse_uses_the_last_value_of_a_repeated_key() {
+        let config = DirectorConfig::parse("build_seconds=10\nbuild_seconds=40");
+        assert_eq!(config.build_seconds, 40.);
+    }
+}
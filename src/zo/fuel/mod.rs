@@ -0,0 +1,135 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_steam_p2p::{
+    networked_events::{event::Networked, register::NetworkedEvents},
+    NetworkId, NetworkIdentity, SteamP2PClient,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::car::Fuel;
+
+use super::{
+    pickup::{PickedUp, Pickup, PickupPlugin},
+    LocalPlayer,
+};
+
+const CANISTER_FUEL: f32 = 40.;
+
+pub struct ZOFuelPlugin;
+impl Plugin for ZOFuelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(PickupPlugin::<FuelCanister>::default())
+            .add_networked_event::<FuelSync>()
+            .add_systems(
+                Update,
+                (
+                    handle_fuel_pickup,
+                    send_fuel_sync.run_if(on_timer(Duration::from_millis(500))),
+                    handle_fuel_sync,
+                    spawn_fuel_gauge,
+                    update_fuel_gauge,
+                ),
+            );
+    }
+}
+
+#[derive(Component, Default)]
+pub struct FuelCanister;
+
+impl Pickup for FuelCanister {
+    const PATH: &'static str = "FuelCanister";
+    const SIZE: Vec2 = Vec2::new(6., 8.);
+    const COLOR: Color = Color::srgb(0.8, 0.15, 0.1);
+    const SPAWN_INTERVAL: Duration = Duration::from_secs(1);
+    const MAX_NEARBY: usize = 3;
+    const MIN_RANGE: f32 = 500.;
+    const VARIATION: f32 = 700.;
+    const DESPAWN_RANGE: f32 = 2000.;
+}
+
+#[derive(Component)]
+struct FuelGauge;
+
+#[derive(Event, Serialize, Clone, Deserialize)]
+pub struct FuelSync {
+    network_id: NetworkId,
+    amount: f32,
+}
+
+fn handle_fuel_pickup(
+    mut picked_up_r: EventReader<PickedUp<FuelCanister>>,
+    mut cars: Query<&mut Fuel>,
+) {
+    for picked_up in picked_up_r.read() {
+        let Ok(mut fuel) = cars.get_mut(picked_up.car) else {
+            continue;
+        };
+        fuel.refuel(CANISTER_FUEL);
+    }
+}
+
+fn send_fuel_sync(
+    client: Res<SteamP2PClient>,
+    cars: Query<(&NetworkIdentity, &Fuel)>,
+    mut sync_w: EventWriter<Networked<FuelSync>>,
+) {
+    for (identity, fuel) in cars.iter() {
+        if identity.id.owner != client.id {
+            continue;
+        }
+        sync_w.send(Networked::new(FuelSync {
+            network_id: identity.id.clone(),
+            amount: fuel.amount,
+        }));
+    }
+}
+
+fn handle_fuel_sync(
+    client: Res<SteamP2PClient>,
+    mut sync_r: EventReader<FuelSync>,
+    mut cars: Query<(&NetworkIdentity, &mut Fuel)>,
+) {
+    for sync in sync_r.read() {
+        if sync.network_id.owner == client.id {
+            continue;
+        }
+        let Some((_, mut fuel)) = cars.iter_mut().find(|(i, _)| i.id == sync.network_id) else {
+            continue;
+        };
+        fuel.amount = sync.amount;
+    }
+}
+
+fn spawn_fuel_gauge(
+    mut commands: Commands,
+    cars: Query<(), (Added<LocalPlayer>, With<Fuel>)>,
+    existing: Query<(), With<FuelGauge>>,
+) {
+    // `LocalPlayer` is added again whenever the character takes over another car.
+    if cars.is_empty() || !existing.is_empty() {
+        return;
+    }
+    commands.spawn((
+        FuelGauge,
+        Text::new(""),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(12.),
+            left: Val::Px(12.),
+            ..default()
+        },
+    ));
+}
+
+fn update_fuel_gauge(
+    cars: Query<&Fuel, With<LocalPlayer>>,
+    mut gauges: Query<&mut Text, With<FuelGauge>>,
+) {
+    let Ok(fuel) = cars.get_single() else {
+        return;
+    };
+    for mut text in gauges.iter_mut() {
+        text.0 = format!("Fuel {:.0}%", fuel.fraction() * 100.);
+    }
+}
@@ -0,0 +1,286 @@
+use std::fs;
+
+use bevy::prelude::*;
+
+use crate::car::Car;
+
+use super::{
+    car::vehicle_class::VehicleClass,
+    character::LocalCharacter,
+    health::{Dead, Kills},
+    lobby::{InLobby, LobbySettings},
+    LocalPlayer,
+};
+
+const SAVE_PATH: &str = "garage.txt";
+const CREDITS_PER_KILL: u32 = 10;
+const MAX_LEVEL: u8 = 3;
+
+pub struct ZOGaragePlugin;
+impl Plugin for ZOGaragePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Garage::load())
+            .add_systems(Startup, spawn_menu)
+            .add_systems(
+                Update,
+                (
+                    buy_upgrades,
+                    update_garage_screen,
+                    close_garage_screen,
+                    bank_earnings,
+                ),
+            );
+    }
+}
+
+/// Upgrade levels of a car, from `0` to `MAX_LEVEL`. Sent after the vehicle class in the
+/// "Player" instantiation payload so every peer builds the car with the same stats.
+#[derive(Component, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Upgrades {
+    pub ram_plates: u8,
+    pub engine: u8,
+    pub tires: u8,
+    pub armor: u8,
+}
+
+impl Upgrades {
+    pub fn to_bytes(self) -> [u8; 4] {
+        [self.ram_plates, self.engine, self.tires, self.armor]
+    }
+
+    /// Reads the upgrade bytes following the vehicle class byte.
+    pub fn from_payload(payload: &[u8]) -> Upgrades {
+        let level = |index: usize| payload.get(index).copied().unwrap_or(0).min(MAX_LEVEL);
+        Upgrades {
+            ram_plates: level(1),
+            engine: level(2),
+            tires: level(3),
+            armor: level(4),
+        }
+    }
+
+    pub fn ram_damage_factor(&self) -> f32 {
+        1. + 0.25 * self.ram_plates as f32
+    }
+
+    pub fn self_damage_factor(&self) -> f32 {
+        1. - 0.2 * self.ram_plates as f32
+    }
+
+    pub fn power_factor(&self) -> f32 {
+        1. + 0.1 * self.engine as f32
+    }
+
+    pub fn grip_factor(&self) -> f32 {
+        1. + 0.08 * self.tires as f32
+    }
+
+    pub fn bonus_health(&self) -> u32 {
+        25 * self.armor as u32
+    }
+}
+
+/// Credits and upgrades kept between runs in `SAVE_PATH`.
+#[derive(Resource, Default)]
+pub struct Garage {
+    pub credits: u32,
+    pub upgrades: Upgrades,
+}
+
+impl Garage {
+    fn load() -> Garage {
+        let Ok(save) = fs::read_to_string(SAVE_PATH) else {
+            return Garage::default();
+        };
+        let mut garage = Garage::default();
+        for line in save.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let Ok(value) = value.trim().parse::<u32>() else {
+                continue;
+            };
+            let level = value.min(MAX_LEVEL as u32) as u8;
+            match key.trim() {
+                "credits" => garage.credits = value,
+                "ram_plates" => garage.upgrades.ram_plates = level,
+                "engine" => garage.upgrades.engine = level,
+                "tires" => garage.upgrades.tires = level,
+                "armor" => garage.upgrades.armor = level,
+                _ => {}
+            }
+        }
+        garage
+    }
+
+    fn save(&self) {
+        let Upgrades {
+            ram_plates,
+            engine,
+            tires,
+            armor,
+        } = self.upgrades;
+        let save = format!(
+            "credits={}\nram_plates={ram_plates}\nengine={engine}\ntires={tires}\narmor={armor}\n",
+            self.credits
+        );
+        if let Err(error) = fs::write(SAVE_PATH, save) {
+            warn!("Couldn't save garage: {error}");
+        }
+    }
+}
+
+#[derive(Component)]
+struct GarageScreen;
+
+#[derive(Component)]
+struct MenuCamera;
+
+fn upgrade_cost(level: u8) -> u32 {
+    100 * (level as u32 + 1)
+}
+
+fn spawn_menu(mut commands: Commands) {
+    commands.spawn((MenuCamera, Camera2d));
+    spawn_garage_screen(&mut commands);
+}
+
+/// Upgrades can only be bought while this screen is open: before the first run and after each.
+fn spawn_garage_screen(commands: &mut Commands) {
+    commands.spawn((
+        GarageScreen,
+        Text::new(""),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(24.),
+            left: Val::Px(24.),
+            ..default()
+        },
+    ));
+}
+
+fn buy_upgrades(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut garage: ResMut<Garage>,
+    screens: Query<(), With<GarageScreen>>,
+) {
+    if screens.is_empty() {
+        return;
+    }
+    let keys_and_upgrades = [
+        (KeyCode::Digit5, 0),
+        (KeyCode::Digit6, 1),
+        (KeyCode::Digit7, 2),
+        (KeyCode::Digit8, 3),
+    ];
+    for (key, index) in keys_and_upgrades {
+        if !keys.just_pressed(key) {
+            continue;
+        }
+        let credits = garage.credits;
+        let mut upgrades = garage.upgrades;
+        let level = match index {
+            0 => &mut upgrades.ram_plates,
+            1 => &mut upgrades.engine,
+            2 => &mut upgrades.tires,
+            _ => &mut upgrades.armor,
+        };
+        let cost = upgrade_cost(*level);
+        if *level >= MAX_LEVEL || credits < cost {
+            continue;
+        }
+        *level += 1;
+        garage.credits -= cost;
+        garage.upgrades = upgrades;
+        garage.save();
+    }
+}
+
+fn update_garage_screen(
+    garage: Res<Garage>,
+    vehicle: Res<VehicleClass>,
+    settings: Res<LobbySettings>,
+    in_lobby: Res<InLobby>,
+    opened: Query<(), Added<GarageScreen>>,
+    mut screens: Query<&mut Text, With<GarageScreen>>,
+) {
+    if !garage.is_changed() && !vehicle.is_changed() && !settings.is_changed() && opened.is_empty()
+    {
+        return;
+    }
+    let Upgrades {
+        ram_plates,
+        engine,
+        tires,
+        armor,
+    } = garage.upgrades;
+    let line = |key: u8, name: &str, level: u8| {
+        if level >= MAX_LEVEL {
+            format!("[{key}] {name} {level}/{MAX_LEVEL}\n")
+        } else {
+            format!(
+                "[{key}] {name} {level}/{MAX_LEVEL} - {} credits\n",
+                upgrade_cost(level)
+            )
+        }
+    };
+
+    let vehicles: String = VehicleClass::ALL
+        .iter()
+        .enumerate()
+        .map(|(index, class)| {
+            let marker = if class == &*vehicle { ">" } else { " " };
+            format!("{marker} [{}] {class:?}\n", index + 1)
+        })
+        .collect();
+
+    // The world of a finished run is still around, so a new one needs a restart.
+    let start = if in_lobby.0 {
+        "Restart the game for a new run"
+    } else {
+        "[C] Create lobby"
+    };
+    for mut text in screens.iter_mut() {
+        text.0 = format!(
+            "GARAGE\nCredits: {}\n\n{}{}{}{}\nVehicle:\n{}\n[P] PvP damage: {}\n{start}",
+            garage.credits,
+            line(5, "Ram plates", ram_plates),
+            line(6, "Engine", engine),
+            line(7, "Tire compound", tires),
+            line(8, "Armor", armor),
+            vehicles,
+            if settings.pvp_damage { "on" } else { "off" },
+        );
+    }
+}
+
+fn close_garage_screen(
+    mut commands: Commands,
+    local: Query<(), Added<LocalPlayer>>,
+    screens: Query<Entity, Or<(With<GarageScreen>, With<MenuCamera>)>>,
+) {
+    if local.is_empty() {
+        return;
+    }
+    for entity in screens.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// The run is over once the local character dies, whether on foot or inside a wreck. The garage
+/// opens again so the earnings can be spent right away.
+fn bank_earnings(
+    mut commands: Commands,
+    mut garage: ResMut<Garage>,
+    characters: Query<(), (With<LocalCharacter>, Added<Dead>)>,
+    cars: Query<&Kills, (With<Car>, With<LocalPlayer>)>,
+) {
+    if characters.is_empty() {
+        return;
+    }
+    for kills in cars.iter() {
+        garage.credits += kills.0 * CREDITS_PER_KILL;
+        garage.save();
+    }
+    spawn_garage_screen(&mut commands);
+}
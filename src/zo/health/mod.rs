@@ -5,12 +5,16 @@ use bevy_steam_p2p::{
 };
 use serde::{Deserialize, Serialize};
 
+/// A hit only earns the kill if the victim dies within this many seconds of it.
+const KILL_CREDIT_SECONDS: f32 = 5.;
+
 pub struct ZOHealthPlugin;
 impl Plugin for ZOHealthPlugin {
     fn build(&self, app: &mut App) {
         app.add_networked_event::<ChangeHealth>()
+            .add_event::<Killed>()
             .add_systems(PreUpdate, handle_death)
-            .add_systems(Update, emit_changes)
+            .add_systems(Update, (emit_changes, tick_invulnerability, count_kills))
             .add_systems(PostUpdate, handle_despawn);
     }
 }
@@ -18,17 +22,42 @@ impl Plugin for ZOHealthPlugin {
 #[derive(Component)]
 pub struct Dead;
 
+/// Ignores damage until the timer finishes, then removes itself.
+#[derive(Component)]
+pub struct Invulnerable(pub Timer);
+
+impl Invulnerable {
+    pub fn from_seconds(seconds: f32) -> Invulnerable {
+        Invulnerable(Timer::from_seconds(seconds, TimerMode::Once))
+    }
+}
+
+/// Number of kills credited to this entity's owner.
+#[derive(Component, Default)]
+pub struct Kills(pub u32);
+
 #[derive(Component)]
 pub struct Health {
     amount: i32,
     max_amount: u32,
     destroy_on_death: bool,
+    /// Source of the last damage with a source, and when it was dealt.
+    last_source: Option<(NetworkId, f32)>,
 }
 
 #[derive(Event, Serialize, Clone, Deserialize)]
 pub struct ChangeHealth {
     pub network_id: NetworkId,
     pub change: i32,
+    /// Entity that caused the change, credited with the kill if this change is fatal.
+    pub source: Option<NetworkId>,
+}
+
+/// Sent locally on every peer when an entity dies.
+#[derive(Event)]
+pub struct Killed {
+    pub victim: Entity,
+    pub killer: Option<NetworkId>,
 }
 
 impl Health {
@@ -37,31 +66,86 @@ impl Health {
             amount: max as i32,
             max_amount: max,
             destroy_on_death,
+            last_source: None,
         }
     }
+
+    pub fn fraction(&self) -> f32 {
+        self.amount.max(0) as f32 / self.max_amount as f32
+    }
 }
 
 fn emit_changes(
+    time: Res<Time>,
     mut changes_r: EventReader<ChangeHealth>,
-    mut healths: Query<(&NetworkIdentity, &mut Health)>,
+    mut healths: Query<(&NetworkIdentity, &mut Health, Has<Invulnerable>)>,
 ) {
     for change in changes_r.read() {
-        let Some((_, mut health)) = healths.iter_mut().find(|(i, h)| i.id == change.network_id)
+        let Some((_, mut health, invulnerable)) = healths
+            .iter_mut()
+            .find(|(i, _, _)| i.id == change.network_id)
         else {
             continue;
         };
+        if invulnerable && change.change < 0 {
+            continue;
+        }
+        if let Some(source) = change.source.as_ref().filter(|_| change.change < 0) {
+            health.last_source = Some((source.clone(), time.elapsed_secs()));
+        }
         health.amount += change.change;
     }
 }
 
-fn handle_death(mut commands: Commands, healths: Query<(Entity, &Health)>) {
+fn tick_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut invulnerables: Query<(Entity, &mut Invulnerable)>,
+) {
+    for (entity, mut invulnerable) in invulnerables.iter_mut() {
+        if invulnerable.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
+}
+
+fn handle_death(
+    mut commands: Commands,
+    time: Res<Time>,
+    healths: Query<(Entity, &Health), Without<Dead>>,
+    mut killed_w: EventWriter<Killed>,
+) {
     for (entity, health) in healths.iter() {
         if health.amount <= 0 {
             commands.entity(entity).insert(Dead);
+            let killer = health
+                .last_source
+                .as_ref()
+                .filter(|(_, at)| time.elapsed_secs() - at <= KILL_CREDIT_SECONDS)
+                .map(|(source, _)| source.clone());
+            killed_w.send(Killed {
+                victim: entity,
+                killer,
+            });
         }
     }
 }
 
+fn count_kills(
+    mut killed_r: EventReader<Killed>,
+    mut killers: Query<(&NetworkIdentity, &mut Kills)>,
+) {
+    for killed in killed_r.read() {
+        let Some(killer) = &killed.killer else {
+            continue;
+        };
+        let Some((_, mut kills)) = killers.iter_mut().find(|(i, _)| i.id == *killer) else {
+            continue;
+        };
+        kills.0 += 1;
+    }
+}
+
 fn handle_despawn(mut commands: Commands, healths: Query<(Entity, &Health), With<Dead>>) {
     for (entity, health) in healths.iter() {
         if health.destroy_on_death {
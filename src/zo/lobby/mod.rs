@@ -1,26 +1,111 @@
-use std::f32::consts::PI;
+use std::{f32::consts::PI, time::Duration};
 
-use bevy::prelude::*;
-use bevy_steam_p2p::{LobbyJoined, NetworkIdentity, SteamP2PClient, UnhandledInstantiation};
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_steam_p2p::{
+    networked_events::{event::Networked, register::NetworkedEvents},
+    LobbyJoined, NetworkIdentity, SteamP2PClient, UnhandledInstantiation,
+};
+use serde::{Deserialize, Serialize};
 
-use crate::zo::car::spawn_car;
+use crate::zo::car::{spawn_car, vehicle_class::VehicleClass};
 
-use super::{spawn_everything, zombies::spawn_zombie};
+use super::{
+    character::spawn_character,
+    fuel::FuelCanister,
+    garage::{Garage, Upgrades},
+    pickup::spawn_pickup,
+    repair::RepairKit,
+    spawn_everything,
+    world::spawn_blood_patch,
+    zombies::{archetype::ZombieArchetype, spawn_zombie},
+};
 
 pub struct ZOLobbyPlugin;
 impl Plugin for ZOLobbyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (menu, on_lobby_join, handle_unhandled_instantiations),
-        );
+        app.init_resource::<VehicleClass>()
+            .init_resource::<LobbySettings>()
+            .init_resource::<InLobby>()
+            .add_networked_event::<LobbySettings>()
+            .add_systems(
+                Update,
+                (
+                    menu,
+                    on_lobby_join,
+                    handle_unhandled_instantiations,
+                    send_lobby_settings.run_if(on_timer(Duration::from_secs(2))),
+                    handle_lobby_settings,
+                ),
+            );
     }
 }
 
-fn menu(client: ResMut<SteamP2PClient>, keys: Res<ButtonInput<KeyCode>>) {
-    if keys.just_pressed(KeyCode::KeyC) {
+/// Host-chosen rules, broadcast to the other players so every peer applies the same ones.
+#[derive(Resource, Event, Serialize, Deserialize, Clone)]
+pub struct LobbySettings {
+    pub pvp_damage: bool,
+}
+
+impl Default for LobbySettings {
+    fn default() -> Self {
+        LobbySettings { pvp_damage: true }
+    }
+}
+
+/// Whether this peer has joined a lobby. The world it spawned stays for the rest of the session,
+/// so only one lobby is created or joined per launch.
+#[derive(Resource, Default)]
+pub struct InLobby(pub bool);
+
+fn menu(
+    client: ResMut<SteamP2PClient>,
+    keys: Res<ButtonInput<KeyCode>>,
+    in_lobby: Res<InLobby>,
+    mut vehicle: ResMut<VehicleClass>,
+    mut settings: ResMut<LobbySettings>,
+) {
+    if keys.just_pressed(KeyCode::KeyC) && !in_lobby.0 {
         client.create_lobby(8);
     }
+    if keys.just_pressed(KeyCode::KeyP) && client.is_lobby_owner().unwrap_or(true) {
+        settings.pvp_damage = !settings.pvp_damage;
+    }
+
+    let vehicle_keys = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+    ];
+    for (key, class) in vehicle_keys.into_iter().zip(VehicleClass::ALL) {
+        if keys.just_pressed(key) && *vehicle != class {
+            *vehicle = class;
+        }
+    }
+}
+
+fn send_lobby_settings(
+    client: Res<SteamP2PClient>,
+    settings: Res<LobbySettings>,
+    mut settings_w: EventWriter<Networked<LobbySettings>>,
+) {
+    if client.is_lobby_owner().is_ok_and(|owner| owner) {
+        settings_w.send(Networked::new(settings.clone()));
+    }
+}
+
+fn handle_lobby_settings(
+    client: Res<SteamP2PClient>,
+    mut settings_r: EventReader<LobbySettings>,
+    mut settings: ResMut<LobbySettings>,
+) {
+    if client.is_lobby_owner().is_ok_and(|owner| owner) {
+        settings_r.clear();
+        return;
+    }
+    if let Some(received) = settings_r.read().last() {
+        *settings = received.clone();
+    }
 }
 
 fn on_lobby_join(
@@ -28,10 +113,17 @@ fn on_lobby_join(
     asset_server: Res<AssetServer>,
     mut join_r: EventReader<LobbyJoined>,
     client: ResMut<SteamP2PClient>,
+    vehicle: Res<VehicleClass>,
+    garage: Res<Garage>,
+    mut in_lobby: ResMut<InLobby>,
 ) {
-    if !join_r.is_empty() {
-        join_r.clear();
-        spawn_everything(commands, asset_server, client);
+    if join_r.is_empty() {
+        return;
+    }
+    join_r.clear();
+    if !in_lobby.0 {
+        in_lobby.0 = true;
+        spawn_everything(commands, asset_server, client, *vehicle, &garage);
     }
 }
 
@@ -52,14 +144,45 @@ fn handle_unhandled_instantiations(
                     &asset_server,
                     data.network_identity.clone(),
                     id,
+                    data.starting_transform,
+                    VehicleClass::from_payload(data.payload.as_deref().unwrap_or_default()),
+                    Upgrades::from_payload(data.payload.as_deref().unwrap_or_default()),
+                    false,
                 );
             }
+            "Bot" => spawn_car(
+                &mut commands,
+                &asset_server,
+                data.network_identity.clone(),
+                id,
+                data.starting_transform,
+                VehicleClass::from_payload(data.payload.as_deref().unwrap_or_default()),
+                Upgrades::default(),
+                true,
+            ),
+            "Character" => spawn_character(
+                data.starting_transform,
+                &mut commands,
+                data.network_identity.clone(),
+                id,
+            ),
             "Zombie" => spawn_zombie(
                 data.starting_transform,
                 &mut commands,
                 &asset_server,
                 data.network_identity.clone(),
                 &mut texture_atlas_layouts,
+                ZombieArchetype::from_payload(data.payload.as_deref().unwrap_or_default()),
+            ),
+            "FuelCanister" => spawn_pickup::<FuelCanister>(
+                data.starting_transform,
+                &mut commands,
+                data.network_identity.clone(),
+            ),
+            "RepairKit" => spawn_pickup::<RepairKit>(
+                data.starting_transform,
+                &mut commands,
+                data.network_identity.clone(),
             ),
             "ZombieCorpse" => {
                 println!("Instantiated corpse");
@@ -70,6 +193,7 @@ fn handle_unhandled_instantiations(
                     Sprite::from_image(asset_server.load("sprites/zombies/dead.png")),
                     data.network_identity.clone(),
                 ));
+                spawn_blood_patch(&mut commands, data.starting_transform.translation.xy());
             }
             _ => {
                 println!("No valid instantiation candidate found");
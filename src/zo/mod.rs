@@ -1,39 +1,117 @@
 use bevy::prelude::*;
 use bevy_steam_p2p::{FilePath, SteamP2PClient};
-use car::ZOCarPlugin;
+use boost::ZOBoostPlugin;
+use bots::ZOBotsPlugin;
+use car::{vehicle_class::VehicleClass, ZOCarPlugin};
+use character::ZOCharacterPlugin;
+use director::ZODirectorPlugin;
+use fuel::ZOFuelPlugin;
+use garage::{Garage, ZOGaragePlugin};
 use health::ZOHealthPlugin;
 use lobby::ZOLobbyPlugin;
-use world::spawn_world;
+use navigation::ZONavigationPlugin;
+use noise::ZONoisePlugin;
+use pickup::ZOPickupPlugin;
+use proximity::ZOProximityPlugin;
+use recovery::ZORecoveryPlugin;
+use repair::ZORepairPlugin;
+use world::{spawn_world, ZOWorldPlugin};
 use zombies::ZOZombiesPlugin;
 
+mod boost;
+mod bots;
 mod car;
+mod character;
+mod director;
+mod fuel;
+mod garage;
 mod health;
 mod lobby;
+mod navigation;
+mod noise;
+mod pickup;
+mod proximity;
+mod recovery;
+mod repair;
 mod world;
 mod zombies;
 
-use crate::{camera_follow::CameraFollowPlugin, car::CarPlugin};
+use crate::{
+    camera_follow::CameraFollowPlugin, car::CarPlugin, particles::ParticlesPlugin,
+    sprite_animation::SpriteAnimationPlugin,
+};
 
 pub struct ZOPlugin;
 
 impl Plugin for ZOPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((CarPlugin, CameraFollowPlugin))
-            .add_plugins((ZOCarPlugin, ZOLobbyPlugin, ZOZombiesPlugin, ZOHealthPlugin));
+        app.add_plugins((
+            CarPlugin,
+            CameraFollowPlugin,
+            ParticlesPlugin,
+            SpriteAnimationPlugin,
+        ))
+        .add_plugins((
+            ZOCarPlugin,
+            ZOLobbyPlugin,
+            ZOZombiesPlugin,
+            ZOHealthPlugin,
+            ZOFuelPlugin,
+            ZORecoveryPlugin,
+            ZOBoostPlugin,
+            ZOBotsPlugin,
+            ZOGaragePlugin,
+            ZOCharacterPlugin,
+            ZORepairPlugin,
+            ZONavigationPlugin,
+            ZONoisePlugin,
+            ZODirectorPlugin,
+            ZOProximityPlugin,
+        ))
+        .add_plugins((ZOWorldPlugin, ZOPickupPlugin));
     }
 }
 
 #[derive(Component)]
 pub struct Player;
 
+/// The car driven with this peer's keyboard.
+#[derive(Component)]
+pub struct LocalPlayer;
+
 pub fn spawn_everything(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut client: ResMut<SteamP2PClient>,
+    vehicle: VehicleClass,
+    garage: &Garage,
 ) {
+    let mut payload = vec![vehicle.to_byte()];
+    payload.extend(garage.upgrades.to_bytes());
     client
-        .instantiate(FilePath::new("Player"), None, Transform::default())
+        .instantiate(FilePath::new("Player"), Some(payload), Transform::default())
         .expect("Couldn't spawn player");
+    client
+        .instantiate(FilePath::new("Character"), None, Transform::default())
+        .expect("Couldn't spawn character");
 
     spawn_world(&mut commands, &asset_server);
 }
+
+/// Shows a large centered message, like the end of a run.
+pub fn spawn_banner(commands: &mut Commands, text: &str) {
+    commands.spawn((
+        Text::new(text),
+        TextFont {
+            font_size: 64.,
+            ..default()
+        },
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(40.),
+            width: Val::Percent(100.),
+            ..default()
+        },
+    ));
+}
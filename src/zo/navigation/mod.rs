@@ -0,0 +1,337 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+};
+
+use avian2d::prelude::{Collider, RigidBody, Sensor};
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+use super::Player;
+
+const CELL_SIZE: f32 = 32.;
+/// Clearance kept from obstacles, roughly a zombie's radius.
+const MARGIN: f32 = 4.;
+/// Upper bound on the cells one search may expand, so a target that is walled off or very far
+/// away can't stall the frame.
+const MAX_EXPANDED: usize = 4000;
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+/// How many cells a flow field reaches out from its target, enough to cover the zombie spawn ring.
+const FLOW_FIELD_RADIUS: i32 = 40;
+const FLOW_FIELD_SIDE: i32 = FLOW_FIELD_RADIUS * 2 + 1;
+/// Cells a target may get away from its field's origin before the field is rebuilt. Zombies that
+/// close can see the target and walk straight at it, so they don't notice the older origin.
+const FLOW_FIELD_SLACK: i32 = 3;
+/// Flow fields rebuilt per frame at most, so a whole lobby moving at once is spread over frames.
+const FLOW_FIELD_REBUILDS: usize = 2;
+const NEIGHBOUR_OFFSETS: [IVec2; 8] = [
+    IVec2::X,
+    IVec2::NEG_X,
+    IVec2::Y,
+    IVec2::NEG_Y,
+    IVec2::ONE,
+    IVec2::NEG_ONE,
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+];
+
+pub struct ZONavigationPlugin;
+impl Plugin for ZONavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NavGrid>()
+            .init_resource::<FlowFields>()
+            .add_systems(PostUpdate, mark_obstacles)
+            .add_systems(Update, update_flow_fields);
+    }
+}
+
+/// Walkability of the world on a uniform grid, filled in from static colliders as they spawn.
+#[derive(Resource, Default)]
+pub struct NavGrid {
+    blocked: HashSet<IVec2>,
+}
+
+/// Direction to walk in from every cell around a target, shared by all zombies chasing it so a
+/// horde costs one search per target instead of one per zombie.
+pub struct FlowField {
+    origin: IVec2,
+    /// Row-major over the `FLOW_FIELD_SIDE` square centered on `origin`.
+    directions: Vec<Option<Vec2>>,
+    /// Set when obstacles changed since the field was built. It is still used until rebuilt.
+    stale: bool,
+}
+
+/// One flow field per `Player`. Zombies are simulated on every peer, so each peer builds the same
+/// fields from the shared world layout.
+#[derive(Resource, Default)]
+pub struct FlowFields {
+    fields: HashMap<Entity, FlowField>,
+}
+
+impl FlowFields {
+    pub fn get(&self, target: Entity) -> Option<&FlowField> {
+        self.fields.get(&target)
+    }
+}
+
+impl FlowField {
+    /// Direction towards the target from `position`, `None` outside the field or in the target's
+    /// own cell.
+    pub fn sample(&self, position: Vec2) -> Option<Vec2> {
+        let index = flow_field_index(NavGrid::cell(position) - self.origin)?;
+        self.directions[index]
+    }
+}
+
+/// Index of a cell, relative to the field's origin, into a field's row-major square.
+fn flow_field_index(offset: IVec2) -> Option<usize> {
+    let local = offset + IVec2::splat(FLOW_FIELD_RADIUS);
+    let inside = local.cmpge(IVec2::ZERO).all() && local.cmplt(IVec2::splat(FLOW_FIELD_SIDE)).all();
+    inside.then_some((local.y * FLOW_FIELD_SIDE + local.x) as usize)
+}
+
+/// Waypoints a zombie walks through to reach its target, nearest first.
+#[derive(Component, Default)]
+pub struct NavPath(pub Vec<Vec2>);
+
+impl NavGrid {
+    pub fn cell(position: Vec2) -> IVec2 {
+        (position / CELL_SIZE).floor().as_ivec2()
+    }
+
+    pub fn cell_center(cell: IVec2) -> Vec2 {
+        (cell.as_vec2() + Vec2::splat(0.5)) * CELL_SIZE
+    }
+
+    pub fn is_blocked(&self, cell: IVec2) -> bool {
+        self.blocked.contains(&cell)
+    }
+
+    /// Whether a straight walk from `from` to `to` stays clear of blocked cells.
+    pub fn line_clear(&self, from: Vec2, to: Vec2) -> bool {
+        let steps = (from.distance(to) / (CELL_SIZE / 2.)).ceil().max(1.) as u32;
+        (0..=steps).all(|step| {
+            let point = from.lerp(to, step as f32 / steps as f32);
+            !self.is_blocked(Self::cell(point))
+        })
+    }
+
+    /// A* over the grid. Returns the waypoints after `start`, ending at `goal`, with corners that
+    /// can be walked straight past removed.
+    pub fn find_path(&self, start: Vec2, goal: Vec2) -> Option<Vec<Vec2>> {
+        let start_cell = Self::cell(start);
+        let goal_cell = Self::cell(goal);
+        if self.is_blocked(goal_cell) {
+            return None;
+        }
+
+        let heuristic = |cell: IVec2| {
+            let d = (cell - goal_cell).abs();
+            STRAIGHT_COST * d.max_element() as u32
+                + (DIAGONAL_COST - STRAIGHT_COST) * d.min_element() as u32
+        };
+
+        let mut open = BinaryHeap::new();
+        let mut came_from = HashMap::<IVec2, IVec2>::new();
+        let mut cost = HashMap::<IVec2, u32>::new();
+        open.push(Reverse((heuristic(start_cell), start_cell.x, start_cell.y)));
+        cost.insert(start_cell, 0);
+
+        let mut expanded = 0;
+        while let Some(Reverse((_, x, y))) = open.pop() {
+            let current = IVec2::new(x, y);
+            if current == goal_cell {
+                return Some(self.smooth(start, goal, self.trace(&came_from, current)));
+            }
+            expanded += 1;
+            if expanded > MAX_EXPANDED {
+                return None;
+            }
+
+            let current_cost = cost[&current];
+            for (neighbour, step_cost) in self.neighbours(current) {
+                let new_cost = current_cost + step_cost;
+                if cost.get(&neighbour).is_some_and(|&c| c <= new_cost) {
+                    continue;
+                }
+                cost.insert(neighbour, new_cost);
+                came_from.insert(neighbour, current);
+                open.push(Reverse((
+                    new_cost + heuristic(neighbour),
+                    neighbour.x,
+                    neighbour.y,
+                )));
+            }
+        }
+        None
+    }
+
+    /// Walkable cells around `cell`. Diagonals are only allowed when both sides are open so paths
+    /// don't clip building corners.
+    fn neighbours(&self, cell: IVec2) -> impl Iterator<Item = (IVec2, u32)> + '_ {
+        walkable_neighbours(cell, move |cell| !self.is_blocked(cell))
+    }
+
+    /// Breadth-first integration field around `origin`, turned into the direction of the cheapest
+    /// neighbour for every reachable cell. Works on flat arrays over the field's square, with the
+    /// obstacles looked up once per cell, since the hash lookups dominated the search.
+    fn flow_field(&self, origin: IVec2) -> FlowField {
+        let size = (FLOW_FIELD_SIDE * FLOW_FIELD_SIDE) as usize;
+        let offset = |index: usize| {
+            IVec2::new(
+                index as i32 % FLOW_FIELD_SIDE,
+                index as i32 / FLOW_FIELD_SIDE,
+            ) - IVec2::splat(FLOW_FIELD_RADIUS)
+        };
+        let open_cells: Vec<bool> = (0..size)
+            .map(|index| !self.is_blocked(origin + offset(index)))
+            .collect();
+        let walkable = |offset: IVec2| flow_field_index(offset).is_some_and(|i| open_cells[i]);
+
+        let mut cost = vec![u32::MAX; size];
+        let mut open = VecDeque::new();
+        cost[flow_field_index(IVec2::ZERO).unwrap()] = 0;
+        open.push_back(IVec2::ZERO);
+
+        while let Some(current) = open.pop_front() {
+            let current_cost = cost[flow_field_index(current).unwrap()];
+            for (neighbour, step_cost) in walkable_neighbours(current, walkable) {
+                let index = flow_field_index(neighbour).unwrap();
+                let new_cost = current_cost + step_cost;
+                if cost[index] <= new_cost {
+                    continue;
+                }
+                cost[index] = new_cost;
+                open.push_back(neighbour);
+            }
+        }
+
+        let directions = (0..size)
+            .map(|index| {
+                let cell = offset(index);
+                if cell == IVec2::ZERO || cost[index] == u32::MAX {
+                    return None;
+                }
+                let (next, _) = walkable_neighbours(cell, walkable)
+                    .map(|(next, _)| (next, cost[flow_field_index(next).unwrap()]))
+                    .min_by_key(|(_, c)| *c)?;
+                Some((next - cell).as_vec2().normalize())
+            })
+            .collect();
+        FlowField {
+            origin,
+            directions,
+            stale: false,
+        }
+    }
+
+    fn trace(&self, came_from: &HashMap<IVec2, IVec2>, mut cell: IVec2) -> Vec<IVec2> {
+        let mut cells = vec![cell];
+        while let Some(&previous) = came_from.get(&cell) {
+            cells.push(previous);
+            cell = previous;
+        }
+        cells.reverse();
+        cells
+    }
+
+    fn smooth(&self, start: Vec2, goal: Vec2, cells: Vec<IVec2>) -> Vec<Vec2> {
+        let mut points: Vec<Vec2> = cells.into_iter().skip(1).map(Self::cell_center).collect();
+        points.pop();
+        points.push(goal);
+
+        let mut waypoints = Vec::new();
+        let mut from = start;
+        let mut index = 0;
+        while index < points.len() {
+            let mut furthest = index;
+            while furthest + 1 < points.len() && self.line_clear(from, points[furthest + 1]) {
+                furthest += 1;
+            }
+            from = points[furthest];
+            waypoints.push(from);
+            index = furthest + 1;
+        }
+        waypoints
+    }
+}
+
+/// Cells around `cell` for which `walkable` holds. Diagonals are only allowed when both sides are
+/// open so paths don't clip building corners.
+fn walkable_neighbours(
+    cell: IVec2,
+    walkable: impl Fn(IVec2) -> bool,
+) -> impl Iterator<Item = (IVec2, u32)> {
+    NEIGHBOUR_OFFSETS.into_iter().filter_map(move |offset| {
+        let next = cell + offset;
+        if !walkable(next) {
+            return None;
+        }
+        if offset.x != 0 && offset.y != 0 {
+            let sides_open = walkable(cell + IVec2::new(offset.x, 0))
+                && walkable(cell + IVec2::new(0, offset.y));
+            return sides_open.then_some((next, DIAGONAL_COST));
+        }
+        Some((next, STRAIGHT_COST))
+    })
+}
+
+fn mark_obstacles(
+    mut grid: ResMut<NavGrid>,
+    colliders: Query<(&Transform, &Collider, &RigidBody), (Added<Collider>, Without<Sensor>)>,
+) {
+    for (transform, collider, rigid_body) in colliders.iter() {
+        if !rigid_body.is_static() {
+            continue;
+        }
+        let aabb = collider.aabb(transform.translation.xy(), transform.rotation);
+        let min = aabb.min - Vec2::splat(MARGIN);
+        let max = aabb.max + Vec2::splat(MARGIN);
+
+        let min_cell = NavGrid::cell(min);
+        let max_cell = NavGrid::cell(max);
+        for x in min_cell.x..=max_cell.x {
+            for y in min_cell.y..=max_cell.y {
+                let cell = IVec2::new(x, y);
+                let center = NavGrid::cell_center(cell);
+                if center.cmpge(min).all() && center.cmple(max).all() {
+                    grid.blocked.insert(cell);
+                }
+            }
+        }
+    }
+}
+
+/// Rebuilds a target's field once it has moved `FLOW_FIELD_SLACK` cells away from the origin, or
+/// new obstacles appeared.
+fn update_flow_fields(
+    grid: Res<NavGrid>,
+    mut flow_fields: ResMut<FlowFields>,
+    targets: Query<(Entity, &Transform), With<Player>>,
+) {
+    if grid.is_changed() {
+        for field in flow_fields.fields.values_mut() {
+            field.stale = true;
+        }
+    }
+    flow_fields
+        .fields
+        .retain(|target, _| targets.contains(*target));
+
+    let stale = targets
+        .iter()
+        .map(|(target, transform)| (target, NavGrid::cell(transform.translation.xy())))
+        .filter(|(target, cell)| {
+            flow_fields.fields.get(target).is_none_or(|field| {
+                field.stale || (field.origin - *cell).abs().max_element() > FLOW_FIELD_SLACK
+            })
+        })
+        .take(FLOW_FIELD_REBUILDS)
+        .collect::<Vec<_>>();
+    for (target, cell) in stale {
+        flow_fields.fields.insert(target, grid.flow_field(cell));
+    }
+}
@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use avian2d::prelude::Collision;
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_steam_p2p::{
+    networked_events::{event::Networked, register::NetworkedEvents},
+    NetworkIdentity, SteamP2PClient,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::car::{Car, CarInput};
+
+use super::Player;
+
+/// Heard from this far with the engine idling.
+const ENGINE_IDLE_RADIUS: f32 = 60.;
+/// Added on top of the idle radius at full throttle.
+const ENGINE_THROTTLE_RADIUS: f32 = 240.;
+const HORN_RADIUS: f32 = 600.;
+const CRASH_MIN_IMPULSE: f32 = 40.;
+const CRASH_RADIUS_PER_IMPULSE: f32 = 3.;
+const CRASH_MAX_RADIUS: f32 = 700.;
+
+pub struct ZONoisePlugin;
+impl Plugin for ZONoisePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_networked_event::<Noise>().add_systems(
+            Update,
+            (
+                engine_noise.run_if(on_timer(Duration::from_millis(500))),
+                horn_noise,
+                crash_noise,
+            ),
+        );
+    }
+}
+
+/// A sound zombies within `radius` of `position` can hear. Sent by the owner of whatever made it,
+/// since only the owner knows its car's throttle.
+#[derive(Event, Serialize, Clone, Deserialize)]
+pub struct Noise {
+    pub position: [f32; 2],
+    pub radius: f32,
+}
+
+fn engine_noise(
+    client: Res<SteamP2PClient>,
+    cars: Query<(&NetworkIdentity, &CarInput, &Transform), (With<Car>, With<Player>)>,
+    mut noise_w: EventWriter<Networked<Noise>>,
+) {
+    for (identity, input, transform) in cars.iter() {
+        if identity.id.owner != client.id {
+            continue;
+        }
+        noise_w.send(Networked::new(Noise {
+            position: transform.translation.xy().to_array(),
+            radius: ENGINE_IDLE_RADIUS + input.throttle.abs() * ENGINE_THROTTLE_RADIUS,
+        }));
+    }
+}
+
+fn horn_noise(
+    client: Res<SteamP2PClient>,
+    cars: Query<(&NetworkIdentity, &CarInput, &Transform), With<Car>>,
+    mut noise_w: EventWriter<Networked<Noise>>,
+) {
+    for (identity, input, transform) in cars.iter() {
+        if identity.id.owner != client.id || !input.horn {
+            continue;
+        }
+        noise_w.send(Networked::new(Noise {
+            position: transform.translation.xy().to_array(),
+            radius: HORN_RADIUS,
+        }));
+    }
+}
+
+fn crash_noise(
+    client: Res<SteamP2PClient>,
+    mut collision_event_reader: EventReader<Collision>,
+    cars: Query<(&NetworkIdentity, &Transform), With<Car>>,
+    mut noise_w: EventWriter<Networked<Noise>>,
+) {
+    for Collision(contacts) in collision_event_reader.read() {
+        if !contacts.collision_started() || contacts.total_normal_impulse < CRASH_MIN_IMPULSE {
+            continue;
+        }
+        let Some((_, transform)) = [contacts.entity1, contacts.entity2]
+            .into_iter()
+            .filter_map(|entity| cars.get(entity).ok())
+            .find(|(identity, _)| identity.id.owner == client.id)
+        else {
+            continue;
+        };
+        noise_w.send(Networked::new(Noise {
+            position: transform.translation.xy().to_array(),
+            radius: (contacts.total_normal_impulse * CRASH_RADIUS_PER_IMPULSE)
+                .min(CRASH_MAX_RADIUS),
+        }));
+    }
+}
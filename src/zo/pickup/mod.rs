@@ -0,0 +1,198 @@
+use std::{marker::PhantomData, time::Duration};
+
+use avian2d::prelude::{
+    Collider, Collision, RigidBody, Sensor, ShapeCastConfig, SpatialQuery, SpatialQueryFilter,
+};
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_steam_p2p::{
+    networked_events::{event::Networked, register::NetworkedEvents},
+    FilePath, NetworkId, NetworkIdentity, SteamP2PClient,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{car::Car, rng::random_point_in_donut, utils::query_double};
+
+use super::Player;
+
+/// Registers the networked event shared by every kind of pickup.
+pub struct ZOPickupPlugin;
+impl Plugin for ZOPickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_networked_event::<PickupCollected>();
+    }
+}
+
+/// An item the host scatters around the players and cars collect by driving over it. Each kind
+/// adds a `PickupPlugin` and applies its effect on `PickedUp` events.
+pub trait Pickup: Component + Default {
+    /// Instantiation path, also matched in the lobby.
+    const PATH: &'static str;
+    const SIZE: Vec2;
+    const COLOR: Color;
+    /// How often the host tops up the pickups around each player.
+    const SPAWN_INTERVAL: Duration;
+    /// Most pickups of this kind kept around a single player.
+    const MAX_NEARBY: usize;
+    const MIN_RANGE: f32;
+    const VARIATION: f32;
+    /// Pickups this far from every player are removed.
+    const DESPAWN_RANGE: f32;
+}
+
+pub struct PickupPlugin<T: Pickup>(PhantomData<T>);
+
+impl<T: Pickup> Default for PickupPlugin<T> {
+    fn default() -> Self {
+        PickupPlugin(PhantomData)
+    }
+}
+
+impl<T: Pickup> Plugin for PickupPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PickedUp<T>>().add_systems(
+            Update,
+            (
+                spawn_pickups::<T>.run_if(on_timer(T::SPAWN_INTERVAL)),
+                despawn_pickups::<T>.run_if(on_timer(Duration::from_secs(1))),
+                collect_pickups::<T>,
+                handle_pickup_collected::<T>,
+            ),
+        );
+    }
+}
+
+/// Sent by the owner of the car that drove over a pickup.
+#[derive(Event, Serialize, Clone, Deserialize)]
+pub struct PickupCollected {
+    car_identity: NetworkId,
+    pickup_identity: NetworkId,
+}
+
+/// Sent locally on every peer when `car` collects a pickup of kind `T`.
+#[derive(Event)]
+pub struct PickedUp<T: Pickup> {
+    pub car: Entity,
+    kind: PhantomData<T>,
+}
+
+pub fn spawn_pickup<T: Pickup>(
+    transform: Transform,
+    commands: &mut Commands,
+    network_identity: NetworkIdentity,
+) {
+    commands.spawn((
+        network_identity,
+        T::default(),
+        transform,
+        RigidBody::Static,
+        Collider::rectangle(T::SIZE.x, T::SIZE.y),
+        Sensor,
+        Sprite::from_color(T::COLOR, T::SIZE),
+    ));
+}
+
+fn spawn_pickups<T: Pickup>(
+    mut client: ResMut<SteamP2PClient>,
+    spatial: SpatialQuery,
+    players: Query<&Transform, With<Player>>,
+    pickups: Query<&Transform, With<T>>,
+) {
+    if !client.is_lobby_owner().is_ok_and(|owner| owner) {
+        return;
+    }
+
+    for player in players.iter() {
+        let count = pickups
+            .iter()
+            .filter(|p| p.translation.distance(player.translation) < T::MIN_RANGE + T::VARIATION)
+            .count();
+        if count >= T::MAX_NEARBY {
+            continue;
+        }
+
+        let sample_point = player.translation.xy()
+            + random_point_in_donut(T::MIN_RANGE, T::MIN_RANGE + T::VARIATION);
+        let shape_cast = spatial.cast_shape(
+            &Collider::rectangle(T::SIZE.x, T::SIZE.y),
+            sample_point,
+            0.,
+            Dir2::new(Vec2::ONE).unwrap(),
+            &ShapeCastConfig::from_max_distance(0.),
+            &SpatialQueryFilter::DEFAULT,
+        );
+        if shape_cast.is_some() {
+            continue;
+        }
+        let _ = client.instantiate(
+            FilePath(T::PATH.to_owned()),
+            None,
+            Transform::from_translation(sample_point.extend(0.)),
+        );
+    }
+}
+
+fn despawn_pickups<T: Pickup>(
+    mut commands: Commands,
+    players: Query<&Transform, With<Player>>,
+    pickups: Query<(Entity, &Transform), With<T>>,
+) {
+    for (pickup, transform) in pickups.iter() {
+        let in_range = players
+            .iter()
+            .any(|p| p.translation.distance(transform.translation) < T::DESPAWN_RANGE);
+        if !in_range {
+            commands.entity(pickup).despawn();
+        }
+    }
+}
+
+fn collect_pickups<T: Pickup>(
+    client: Res<SteamP2PClient>,
+    mut collision_event_reader: EventReader<Collision>,
+    cars: Query<&NetworkIdentity, With<Car>>,
+    pickups: Query<&NetworkIdentity, With<T>>,
+    mut collected_w: EventWriter<Networked<PickupCollected>>,
+) {
+    for Collision(contacts) in collision_event_reader.read() {
+        if !contacts.collision_started() {
+            continue;
+        }
+        let Some((car, pickup)) = query_double(&cars, &pickups, contacts.entity1, contacts.entity2)
+        else {
+            continue;
+        };
+        if car.id.owner != client.id {
+            continue;
+        }
+        collected_w.send(Networked::new(PickupCollected {
+            car_identity: car.id.clone(),
+            pickup_identity: pickup.id.clone(),
+        }));
+    }
+}
+
+fn handle_pickup_collected<T: Pickup>(
+    mut commands: Commands,
+    mut collected_r: EventReader<PickupCollected>,
+    cars: Query<(Entity, &NetworkIdentity), With<Car>>,
+    pickups: Query<(Entity, &NetworkIdentity), With<T>>,
+    mut picked_up_w: EventWriter<PickedUp<T>>,
+) {
+    for collected in collected_r.read() {
+        let Some((pickup, _)) = pickups
+            .iter()
+            .find(|(_, i)| i.id == collected.pickup_identity)
+        else {
+            continue;
+        };
+        commands.entity(pickup).despawn();
+
+        let Some((car, _)) = cars.iter().find(|(_, i)| i.id == collected.car_identity) else {
+            continue;
+        };
+        picked_up_w.send(PickedUp {
+            car,
+            kind: PhantomData,
+        });
+    }
+}
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+
+use crate::spatial_grid::SpatialGrid;
+
+use super::{zombies::Zombie, Player};
+
+/// Sized for the sight and separation checks, which are the most frequent queries.
+const ZOMBIE_CELL_SIZE: f32 = 64.;
+/// Players are few and queried with spawn-ring sized radii.
+const PLAYER_CELL_SIZE: f32 = 256.;
+
+pub struct ZOProximityPlugin;
+impl Plugin for ZOProximityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ZombieGrid>()
+            .init_resource::<PlayerGrid>()
+            .add_systems(PreUpdate, (index_zombies, index_players))
+            .add_systems(FixedUpdate, index_zombies);
+    }
+}
+
+/// Every zombie by position, rebuilt each frame and again each fixed step for separation.
+#[derive(Resource, Deref)]
+pub struct ZombieGrid(SpatialGrid<Entity>);
+
+impl Default for ZombieGrid {
+    fn default() -> Self {
+        ZombieGrid(SpatialGrid::new(ZOMBIE_CELL_SIZE))
+    }
+}
+
+/// Every `Player` by position, rebuilt each frame.
+#[derive(Resource, Deref)]
+pub struct PlayerGrid(SpatialGrid<Entity>);
+
+impl Default for PlayerGrid {
+    fn default() -> Self {
+        PlayerGrid(SpatialGrid::new(PLAYER_CELL_SIZE))
+    }
+}
+
+pub fn index_zombies(
+    mut grid: ResMut<ZombieGrid>,
+    zombies: Query<(Entity, &Transform), With<Zombie>>,
+) {
+    grid.0.clear();
+    for (zombie, transform) in zombies.iter() {
+        grid.0.insert(zombie, transform.translation.xy());
+    }
+}
+
+fn index_players(mut grid: ResMut<PlayerGrid>, players: Query<(Entity, &Transform), With<Player>>) {
+    grid.0.clear();
+    for (player, transform) in players.iter() {
+        grid.0.insert(player, transform.translation.xy());
+    }
+}
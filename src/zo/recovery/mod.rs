@@ -0,0 +1,231 @@
+use avian2d::prelude::{
+    AngularVelocity, Collider, LinearVelocity, Sensor, SpatialQuery, SpatialQueryFilter,
+};
+use bevy::prelude::*;
+use bevy_steam_p2p::{
+    networked_events::{event::Networked, register::NetworkedEvents},
+    NetworkId, NetworkIdentity, SteamP2PClient,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::car::CarInput;
+
+use super::{health::Invulnerable, world::nearest_street_points, LocalPlayer, Player};
+
+/// A car that hasn't got this far from where it was `STUCK_SECONDS` ago, with the throttle held
+/// the whole time, counts as stuck.
+const STUCK_DISTANCE: f32 = 24.;
+const STUCK_SECONDS: f32 = 3.;
+const RESET_COOLDOWN: f32 = 15.;
+const INVULNERABLE_SECONDS: f32 = 3.;
+
+pub struct ZORecoveryPlugin;
+impl Plugin for ZORecoveryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_networked_event::<CarReset>().add_systems(
+            Update,
+            (
+                detect_stuck,
+                reset_car,
+                handle_car_reset,
+                spawn_reset_prompt,
+                update_reset_prompt,
+            ),
+        );
+    }
+}
+
+/// Tracks how long a car has been without making progress.
+#[derive(Component)]
+pub struct Recovery {
+    /// Where the car was when it last made progress.
+    anchor: Vec2,
+    stuck_time: f32,
+    cooldown: Timer,
+}
+
+impl Recovery {
+    pub fn new() -> Recovery {
+        let mut cooldown = Timer::from_seconds(RESET_COOLDOWN, TimerMode::Once);
+        cooldown.tick(cooldown.duration());
+        Recovery {
+            anchor: Vec2::ZERO,
+            stuck_time: 0.,
+            cooldown,
+        }
+    }
+
+    pub fn can_reset(&self) -> bool {
+        self.stuck_time >= STUCK_SECONDS && self.cooldown.finished()
+    }
+}
+
+impl Default for Recovery {
+    fn default() -> Self {
+        Recovery::new()
+    }
+}
+
+#[derive(Component)]
+struct ResetPrompt;
+
+#[derive(Event, Serialize, Clone, Deserialize)]
+pub struct CarReset {
+    network_id: NetworkId,
+}
+
+fn detect_stuck(
+    time: Res<Time>,
+    client: Res<SteamP2PClient>,
+    mut cars: Query<
+        (
+            &NetworkIdentity,
+            &Transform,
+            &CarInput,
+            &mut Recovery,
+            Has<LocalPlayer>,
+        ),
+        With<Player>,
+    >,
+) {
+    for (identity, transform, input, mut recovery, local) in cars.iter_mut() {
+        recovery.cooldown.tick(time.delta());
+        // A driver who doesn't own the car tracks it too, to know when to show the prompt.
+        if identity.id.owner != client.id && !local {
+            continue;
+        }
+        let position = transform.translation.xy();
+        // A parked car isn't stuck, so letting go of the throttle starts the window over.
+        if input.throttle == 0. || position.distance(recovery.anchor) > STUCK_DISTANCE {
+            recovery.anchor = position;
+            recovery.stuck_time = 0.;
+        } else {
+            recovery.stuck_time += time.delta_secs();
+        }
+    }
+}
+
+fn reset_car(
+    client: Res<SteamP2PClient>,
+    spatial: SpatialQuery,
+    sensors: Query<(), With<Sensor>>,
+    mut cars: Query<
+        (
+            Entity,
+            &NetworkIdentity,
+            &CarInput,
+            &Collider,
+            &mut Transform,
+            &mut LinearVelocity,
+            &mut AngularVelocity,
+            &mut Recovery,
+        ),
+        With<Player>,
+    >,
+    mut reset_w: EventWriter<Networked<CarReset>>,
+) {
+    for (
+        car,
+        identity,
+        input,
+        collider,
+        mut transform,
+        mut velocity,
+        mut angular_velocity,
+        mut recovery,
+    ) in cars.iter_mut()
+    {
+        if identity.id.owner != client.id || !input.reset || !recovery.can_reset() {
+            continue;
+        }
+        let position = transform.translation.xy();
+        let heading = transform.up().xy();
+        let filter = SpatialQueryFilter::from_excluded_entities([car]);
+
+        let free_spot = nearest_street_points(position)
+            .into_iter()
+            .map(|(point, angle)| {
+                let along = Vec2::from_angle(angle).perp();
+                let angle = if along.dot(heading) < 0. {
+                    angle + std::f32::consts::PI
+                } else {
+                    angle
+                };
+                (point, angle)
+            })
+            .find(|(point, angle)| {
+                spatial
+                    .shape_intersections(collider, *point, *angle, &filter)
+                    .into_iter()
+                    .all(|entity| sensors.contains(entity))
+            });
+        let Some((point, angle)) = free_spot else {
+            continue;
+        };
+
+        transform.translation = point.extend(transform.translation.z);
+        transform.rotation = Quat::from_rotation_z(angle);
+        **velocity = Vec2::ZERO;
+        **angular_velocity = 0.;
+        recovery.anchor = point;
+        recovery.stuck_time = 0.;
+        recovery.cooldown.reset();
+        reset_w.send(Networked::new(CarReset {
+            network_id: identity.id.clone(),
+        }));
+    }
+}
+
+fn handle_car_reset(
+    mut commands: Commands,
+    mut reset_r: EventReader<CarReset>,
+    mut cars: Query<(Entity, &NetworkIdentity, &mut Recovery)>,
+) {
+    for reset in reset_r.read() {
+        let Some((car, _, mut recovery)) =
+            cars.iter_mut().find(|(_, i, _)| i.id == reset.network_id)
+        else {
+            continue;
+        };
+        recovery.stuck_time = 0.;
+        recovery.cooldown.reset();
+        commands
+            .entity(car)
+            .insert(Invulnerable::from_seconds(INVULNERABLE_SECONDS));
+    }
+}
+
+fn spawn_reset_prompt(
+    mut commands: Commands,
+    cars: Query<(), (Added<LocalPlayer>, With<Recovery>)>,
+    existing: Query<(), With<ResetPrompt>>,
+) {
+    if cars.is_empty() || !existing.is_empty() {
+        return;
+    }
+    commands.spawn((
+        ResetPrompt,
+        Text::new("Stuck? Press R to reset"),
+        Visibility::Hidden,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(40.),
+            left: Val::Px(12.),
+            ..default()
+        },
+    ));
+}
+
+fn update_reset_prompt(
+    cars: Query<&Recovery, (With<LocalPlayer>, With<Player>)>,
+    mut prompts: Query<&mut Visibility, With<ResetPrompt>>,
+) {
+    let can_reset = cars.iter().any(|recovery| recovery.can_reset());
+    for mut visibility in prompts.iter_mut() {
+        *visibility = if can_reset {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
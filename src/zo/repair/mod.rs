@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::car::{tire::Tire, Car};
+
+use super::{
+    pickup::{PickedUp, Pickup, PickupPlugin},
+    LocalPlayer,
+};
+
+pub struct ZORepairPlugin;
+impl Plugin for ZORepairPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(PickupPlugin::<RepairKit>::default())
+            .add_systems(
+                Update,
+                (handle_repair_pickup, spawn_tire_gauge, update_tire_gauge),
+            );
+    }
+}
+
+/// Puts new tires on the car that drives over it.
+#[derive(Component, Default)]
+pub struct RepairKit;
+
+impl Pickup for RepairKit {
+    const PATH: &'static str = "RepairKit";
+    const SIZE: Vec2 = Vec2::new(8., 8.);
+    const COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+    const SPAWN_INTERVAL: Duration = Duration::from_secs(3);
+    const MAX_NEARBY: usize = 1;
+    const MIN_RANGE: f32 = 700.;
+    const VARIATION: f32 = 900.;
+    const DESPAWN_RANGE: f32 = 2500.;
+}
+
+#[derive(Component)]
+struct TireGauge;
+
+fn handle_repair_pickup(
+    mut picked_up_r: EventReader<PickedUp<RepairKit>>,
+    cars: Query<&Children, With<Car>>,
+    mut tires: Query<&mut Tire>,
+) {
+    for picked_up in picked_up_r.read() {
+        let Ok(children) = cars.get(picked_up.car) else {
+            continue;
+        };
+        let mut iter = tires.iter_many_mut(children);
+        while let Some(mut tire) = iter.fetch_next() {
+            tire.repair();
+        }
+    }
+}
+
+fn spawn_tire_gauge(
+    mut commands: Commands,
+    cars: Query<(), (Added<LocalPlayer>, With<Car>)>,
+    existing: Query<(), With<TireGauge>>,
+) {
+    if cars.is_empty() || !existing.is_empty() {
+        return;
+    }
+    commands.spawn((
+        TireGauge,
+        Text::new(""),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(40.),
+            right: Val::Px(12.),
+            ..default()
+        },
+    ));
+}
+
+/// Shows the most worn tire, since that is the one that lets go first.
+fn update_tire_gauge(
+    cars: Query<&Children, With<LocalPlayer>>,
+    tires: Query<&Tire>,
+    mut gauges: Query<&mut Text, With<TireGauge>>,
+) {
+    let Ok(children) = cars.get_single() else {
+        return;
+    };
+    let condition = tires
+        .iter_many(children)
+        .map(Tire::condition)
+        .fold(1., f32::min);
+    for mut text in gauges.iter_mut() {
+        text.0 = format!("Tires {:.0}%", condition * 100.);
+    }
+}
@@ -1,25 +1,143 @@
 use avian2d::prelude::*;
 use bevy::prelude::*;
 
+use crate::car::surface::Surface;
+
+const BUILDING_SIZE: f32 = 128.;
+const STREET_SIZE: f32 = 128.;
+const SPACING: f32 = BUILDING_SIZE + STREET_SIZE;
+const BLOOD_PATCH_SECONDS: f32 = 30.;
+const BLOOD_PATCH_FADE_SECONDS: f32 = 5.;
+
+pub struct ZOWorldPlugin;
+
+impl Plugin for ZOWorldPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, dry_blood_patches);
+    }
+}
+
+/// A blood surface left by a dead zombie. It fades out and is removed after a while so corpses
+/// don't pile up colliders for the rest of the run.
+#[derive(Component)]
+pub struct BloodPatch {
+    lifetime: Timer,
+}
+
 pub fn spawn_world(commands: &mut Commands, asset_server: &AssetServer) {
-    let building_size = 128.;
-    let street_size = 128.;
-    let spacing = building_size + street_size;
-    let offset = -((building_size / 2.) + (street_size / 2.));
+    let offset = -((BUILDING_SIZE / 2.) + (STREET_SIZE / 2.));
 
     for x in -32..32 {
         for y in -32..32 {
-            commands.spawn((
-                Transform::from_translation(Vec3::new(
-                    offset + x as f32 * spacing,
-                    offset + y as f32 * spacing,
-                    0.,
-                )),
-                Sprite::from_image(asset_server.load("sprites/building.png")),
-                RigidBody::Static,
-                Collider::rectangle(building_size, building_size),
-                //  Transform::from_rotation(Quat::from_rotation_z(PI * (x % y) as f32)),
-            ));
+            let position = Vec2::new(offset + x as f32 * SPACING, offset + y as f32 * SPACING);
+
+            // Layouts must match on every peer, so lots and slicks are placed by pattern, not rng.
+            if (x * 7 + y * 13).rem_euclid(11) == 0 {
+                spawn_surface(
+                    commands,
+                    position,
+                    Vec2::splat(BUILDING_SIZE),
+                    Surface::GRASS,
+                    Color::srgb(0.25, 0.45, 0.2),
+                );
+            } else {
+                commands.spawn((
+                    Transform::from_translation(position.extend(0.)),
+                    Sprite::from_image(asset_server.load("sprites/building.png")),
+                    RigidBody::Static,
+                    Collider::rectangle(BUILDING_SIZE, BUILDING_SIZE),
+                    //  Transform::from_rotation(Quat::from_rotation_z(PI * (x % y) as f32)),
+                ));
+            }
+
+            if (x * 31 + y * 17).rem_euclid(23) == 0 {
+                spawn_surface(
+                    commands,
+                    Vec2::new(x as f32, y as f32) * SPACING,
+                    Vec2::new(48., 32.),
+                    Surface::OIL,
+                    Color::srgba(0.05, 0.05, 0.08, 0.9),
+                );
+            }
         }
     }
 }
+
+/// Center of the street intersection at grid coordinates `cell`.
+pub fn street_intersection(cell: IVec2) -> Vec2 {
+    cell.as_vec2() * SPACING
+}
+
+/// Grid coordinates of the street intersection closest to `position`.
+pub fn nearest_intersection(position: Vec2) -> IVec2 {
+    (position / SPACING).round().as_ivec2()
+}
+
+/// Points on the center lines of the streets around `position`, closest first, with the rotation
+/// that faces along the street.
+pub fn nearest_street_points(position: Vec2) -> Vec<(Vec2, f32)> {
+    let cell = (position / SPACING).round();
+    let mut points = Vec::new();
+
+    for offset in -1..=1 {
+        let line = (cell + Vec2::splat(offset as f32)) * SPACING;
+        points.push((Vec2::new(line.x, position.y), 0.));
+        points.push((Vec2::new(position.x, line.y), std::f32::consts::FRAC_PI_2));
+        for other in -1..=1 {
+            points.push((Vec2::new(line.x, (cell.y + other as f32) * SPACING), 0.));
+        }
+    }
+    points.sort_by(|(a, _), (b, _)| {
+        a.distance_squared(position)
+            .total_cmp(&b.distance_squared(position))
+    });
+    points
+}
+
+pub fn spawn_blood_patch(commands: &mut Commands, position: Vec2) {
+    let patch = spawn_surface(
+        commands,
+        position,
+        Vec2::splat(16.),
+        Surface::BLOOD,
+        Color::srgba(0.4, 0., 0., 0.8),
+    );
+    commands.entity(patch).insert(BloodPatch {
+        lifetime: Timer::from_seconds(BLOOD_PATCH_SECONDS, TimerMode::Once),
+    });
+}
+
+fn dry_blood_patches(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut patches: Query<(Entity, &mut BloodPatch, &mut Sprite)>,
+) {
+    for (entity, mut patch, mut sprite) in patches.iter_mut() {
+        patch.lifetime.tick(time.delta());
+        if patch.lifetime.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let fade = (patch.lifetime.remaining_secs() / BLOOD_PATCH_FADE_SECONDS).min(1.);
+        sprite.color.set_alpha(0.8 * fade);
+    }
+}
+
+fn spawn_surface(
+    commands: &mut Commands,
+    position: Vec2,
+    size: Vec2,
+    surface: Surface,
+    color: Color,
+) -> Entity {
+    commands
+        .spawn((
+            Transform::from_translation(position.extend(-0.5)),
+            Sprite::from_color(color, size),
+            RigidBody::Static,
+            Collider::rectangle(size.x, size.y),
+            Sensor,
+            surface,
+        ))
+        .id()
+}
@@ -0,0 +1,62 @@
+use avian2d::prelude::LinearVelocity;
+use bevy::prelude::*;
+
+use crate::sprite_animation::{Clip, SpriteAnimation};
+
+use super::Zombie;
+
+/// Speed at which the walk clip plays at its normal rate.
+const WALK_REFERENCE_SPEED: f32 = 60.;
+const IDLE_SPEED: f32 = 5.;
+
+pub struct ZOZombieAnimationPlugin;
+impl Plugin for ZOZombieAnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, pick_zombie_clips);
+    }
+}
+
+/// Size of `zombies.png` in frames. Each archetype gets its own row once its art exists; until
+/// then the sheet is a single frame.
+pub const FRAMES: usize = 1;
+pub const ROWS: usize = 1;
+
+/// Clip indices of `zombie_animation`.
+pub const IDLE: usize = 0;
+pub const WALK: usize = 1;
+pub const ATTACK: usize = 2;
+
+/// Idle, walk and attack clips over a row of `zombies.png`, picked with `SpriteAnimation::offset`.
+/// Frames past the end of the row show its last frame, so the clips keep their timing on a shorter
+/// sheet.
+pub fn zombie_animation() -> SpriteAnimation {
+    let frames = |indices: &[usize]| -> Vec<usize> {
+        indices
+            .iter()
+            .map(|index| (*index).min(FRAMES - 1))
+            .collect()
+    };
+    SpriteAnimation::new(vec![
+        Clip::new(frames(&[0]), 1., true),
+        Clip::new(frames(&[1, 0, 2, 0]), 8., true),
+        Clip::new(frames(&[1, 2, 2, 0]), 10., false),
+    ])
+}
+
+/// Movement clips follow each peer's simulated velocity. Attacks are started from networked events
+/// so every peer plays them at the same moment.
+fn pick_zombie_clips(mut zombies: Query<(&LinearVelocity, &mut SpriteAnimation), With<Zombie>>) {
+    for (velocity, mut animation) in zombies.iter_mut() {
+        if animation.current() == ATTACK && !animation.is_finished() {
+            continue;
+        }
+        let speed = velocity.length();
+        if speed < IDLE_SPEED {
+            animation.play(IDLE);
+            animation.speed = 1.;
+        } else {
+            animation.play(WALK);
+            animation.speed = speed / WALK_REFERENCE_SPEED;
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+
+use crate::rng::random_weighted;
+
+/// Kind of zombie. Chosen by the host from weighted spawn tables and sent as the first byte of the
+/// "Zombie" instantiation payload so every peer builds the same zombie.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ZombieArchetype {
+    #[default]
+    Walker,
+    Runner,
+    Brute,
+    Crawler,
+    Spitter,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ZombieBehavior {
+    /// Walks into its target.
+    Melee,
+    /// Keeps its distance and spits from up to `range` away.
+    Ranged { range: f32 },
+}
+
+pub struct ZombieSpec {
+    /// Force pushing the zombie towards its target.
+    pub speed: f32,
+    pub health: u32,
+    pub mass: f32,
+    pub radius: f32,
+    pub color: Color,
+    pub behavior: ZombieBehavior,
+    /// Health taken by each hit, in melee or from a spit.
+    pub damage: i32,
+    /// Seconds between attacks.
+    pub attack_cooldown: f32,
+    /// Relative chance of spawning.
+    pub weight: f32,
+    /// Row of `zombies.png` holding this archetype's animation frames. The sheet only has row `0`
+    /// for now, so archetypes are told apart by `color`.
+    pub atlas_index: usize,
+}
+
+impl ZombieArchetype {
+    pub const ALL: [ZombieArchetype; 5] = [
+        ZombieArchetype::Walker,
+        ZombieArchetype::Runner,
+        ZombieArchetype::Brute,
+        ZombieArchetype::Crawler,
+        ZombieArchetype::Spitter,
+    ];
+
+    pub fn to_byte(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_byte(byte: u8) -> Option<ZombieArchetype> {
+        ZombieArchetype::ALL.get(byte as usize).copied()
+    }
+
+    pub fn from_payload(payload: &[u8]) -> ZombieArchetype {
+        payload
+            .first()
+            .and_then(|byte| ZombieArchetype::from_byte(*byte))
+            .unwrap_or_default()
+    }
+
+    pub const SPECIAL: [ZombieArchetype; 2] = [ZombieArchetype::Brute, ZombieArchetype::Spitter];
+
+    pub fn is_special(self) -> bool {
+        ZombieArchetype::SPECIAL.contains(&self)
+    }
+
+    /// One of the everyday zombies, picked by weight.
+    pub fn random_common() -> ZombieArchetype {
+        let weights = ZombieArchetype::ALL.map(|archetype| {
+            if archetype.is_special() {
+                0.
+            } else {
+                archetype.spec().weight
+            }
+        });
+        ZombieArchetype::ALL[random_weighted(&weights)]
+    }
+
+    /// One of the special zombies the director slips into a fight, picked by weight.
+    pub fn random_special() -> ZombieArchetype {
+        let weights = ZombieArchetype::SPECIAL.map(|archetype| archetype.spec().weight);
+        ZombieArchetype::SPECIAL[random_weighted(&weights)]
+    }
+
+    pub fn spec(self) -> ZombieSpec {
+        match self {
+            ZombieArchetype::Walker => ZombieSpec {
+                speed: 300.,
+                health: 100,
+                mass: 0.1,
+                radius: 4.,
+                color: Color::WHITE,
+                behavior: ZombieBehavior::Melee,
+                damage: 6,
+                attack_cooldown: 1.2,
+                weight: 50.,
+                atlas_index: 0,
+            },
+            ZombieArchetype::Runner => ZombieSpec {
+                speed: 450.,
+                health: 60,
+                mass: 0.08,
+                radius: 3.5,
+                color: Color::srgb(0.75, 0.8, 1.),
+                behavior: ZombieBehavior::Melee,
+                damage: 4,
+                attack_cooldown: 0.8,
+                weight: 20.,
+                atlas_index: 0,
+            },
+            // Heavy enough to shove a car aside and tough enough to survive a ram.
+            ZombieArchetype::Brute => ZombieSpec {
+                speed: 210.,
+                health: 300,
+                mass: 2.,
+                radius: 7.,
+                color: Color::srgb(1., 0.7, 0.7),
+                behavior: ZombieBehavior::Melee,
+                damage: 20,
+                attack_cooldown: 2.,
+                weight: 8.,
+                atlas_index: 0,
+            },
+            ZombieArchetype::Crawler => ZombieSpec {
+                speed: 150.,
+                health: 50,
+                mass: 0.05,
+                radius: 3.,
+                color: Color::srgb(0.7, 0.9, 0.7),
+                behavior: ZombieBehavior::Melee,
+                damage: 5,
+                attack_cooldown: 1.,
+                weight: 15.,
+                atlas_index: 0,
+            },
+            ZombieArchetype::Spitter => ZombieSpec {
+                speed: 240.,
+                health: 70,
+                mass: 0.1,
+                radius: 4.,
+                color: Color::srgb(0.8, 1., 0.4),
+                behavior: ZombieBehavior::Ranged { range: 120. },
+                damage: 8,
+                attack_cooldown: 2.5,
+                weight: 7.,
+                atlas_index: 0,
+            },
+        }
+    }
+}
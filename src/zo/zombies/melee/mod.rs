@@ -0,0 +1,170 @@
+use bevy::prelude::*;
+use bevy_steam_p2p::{
+    networked_events::{event::Networked, register::NetworkedEvents},
+    NetworkId, NetworkIdentity, SteamP2PClient,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    sprite_animation::SpriteAnimation,
+    zo::health::{ChangeHealth, Dead},
+};
+
+use super::{
+    animation::ATTACK,
+    archetype::{ZombieArchetype, ZombieBehavior},
+    state::{ZombieState, ATTACK_RANGE},
+    Zombie,
+};
+
+const WIND_UP_SECONDS: f32 = 0.4;
+/// Extra reach when the blow lands, so a target backing off during the wind-up can still be hit.
+const HIT_RANGE_FACTOR: f32 = 1.25;
+
+pub struct ZOMeleePlugin;
+impl Plugin for ZOMeleePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_networked_event::<ZombieAttack>().add_systems(
+            Update,
+            (
+                add_melee_cooldowns,
+                start_attacks,
+                handle_zombie_attack,
+                land_attacks,
+            ),
+        );
+    }
+}
+
+#[derive(Component)]
+struct MeleeCooldown(Timer);
+
+/// A swing in progress, landing on `target` when the timer finishes.
+#[derive(Component)]
+struct WindUp {
+    timer: Timer,
+    target: Entity,
+}
+
+/// Sent by the host when a zombie starts swinging at `target`.
+#[derive(Event, Serialize, Clone, Deserialize)]
+pub struct ZombieAttack {
+    zombie_identity: NetworkId,
+    target: NetworkId,
+}
+
+fn add_melee_cooldowns(
+    mut commands: Commands,
+    zombies: Query<(Entity, &ZombieArchetype), Added<ZombieArchetype>>,
+) {
+    for (zombie, archetype) in zombies.iter() {
+        let spec = archetype.spec();
+        if spec.behavior == ZombieBehavior::Melee {
+            commands
+                .entity(zombie)
+                .insert(MeleeCooldown(Timer::from_seconds(
+                    spec.attack_cooldown,
+                    TimerMode::Once,
+                )));
+        }
+    }
+}
+
+fn start_attacks(
+    time: Res<Time>,
+    client: Res<SteamP2PClient>,
+    mut zombies: Query<
+        (&NetworkIdentity, &Zombie, &mut MeleeCooldown),
+        (Without<WindUp>, Without<Dead>),
+    >,
+    targets: Query<&NetworkIdentity>,
+    mut attack_w: EventWriter<Networked<ZombieAttack>>,
+) {
+    if !client.is_lobby_owner().is_ok_and(|owner| owner) {
+        return;
+    }
+    for (identity, zombie, mut cooldown) in zombies.iter_mut() {
+        if !cooldown.0.tick(time.delta()).finished() {
+            continue;
+        }
+        let ZombieState::Attack(target, _) = zombie.state else {
+            continue;
+        };
+        let Ok(target) = targets.get(target) else {
+            continue;
+        };
+        cooldown.0.reset();
+        attack_w.send(Networked::new(ZombieAttack {
+            zombie_identity: identity.id.clone(),
+            target: target.id.clone(),
+        }));
+    }
+}
+
+/// Every peer plays the wind-up so the swing looks the same everywhere.
+fn handle_zombie_attack(
+    mut commands: Commands,
+    mut attack_r: EventReader<ZombieAttack>,
+    identities: Query<(Entity, &NetworkIdentity)>,
+    mut zombies: Query<&mut SpriteAnimation, With<Zombie>>,
+) {
+    for attack in attack_r.read() {
+        let entity_of = |id: &NetworkId| {
+            identities
+                .iter()
+                .find(|(_, i)| i.id == *id)
+                .map(|(entity, _)| entity)
+        };
+        let (Some(zombie), Some(target)) = (
+            entity_of(&attack.zombie_identity),
+            entity_of(&attack.target),
+        ) else {
+            continue;
+        };
+        let Ok(mut animation) = zombies.get_mut(zombie) else {
+            continue;
+        };
+        animation.restart(ATTACK);
+        animation.speed = 1.;
+        commands.entity(zombie).insert(WindUp {
+            timer: Timer::from_seconds(WIND_UP_SECONDS, TimerMode::Once),
+            target,
+        });
+    }
+}
+
+/// The host decides whether the swing connects once the wind-up is over.
+fn land_attacks(
+    mut commands: Commands,
+    time: Res<Time>,
+    client: Res<SteamP2PClient>,
+    mut zombies: Query<(Entity, &ZombieArchetype, &Transform, &mut WindUp, Has<Dead>)>,
+    targets: Query<(&NetworkIdentity, &Transform), Without<Dead>>,
+    mut change_health_w: EventWriter<Networked<ChangeHealth>>,
+) {
+    let host = client.is_lobby_owner().is_ok_and(|owner| owner);
+    for (zombie, archetype, transform, mut wind_up, dead) in zombies.iter_mut() {
+        if !wind_up.timer.tick(time.delta()).finished() {
+            continue;
+        }
+        commands.entity(zombie).remove::<WindUp>();
+        if !host || dead {
+            continue;
+        }
+        let Ok((identity, target_transform)) = targets.get(wind_up.target) else {
+            continue;
+        };
+        let distance = transform
+            .translation
+            .xy()
+            .distance(target_transform.translation.xy());
+        if distance > ATTACK_RANGE * HIT_RANGE_FACTOR {
+            continue;
+        }
+        change_health_w.send(Networked::new(ChangeHealth {
+            network_id: identity.id.clone(),
+            change: -archetype.spec().damage,
+            source: None,
+        }));
+    }
+}
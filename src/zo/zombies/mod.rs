@@ -8,33 +8,60 @@ use avian2d::{
         SpatialQueryFilter,
     },
 };
-use bevy::{prelude::*, time::common_conditions::on_timer, utils::HashSet};
-use bevy_steam_p2p::{
-    networked_events::{event::Networked, register::NetworkedEvents},
-    serde::Serialize,
-    FilePath, NetworkId, NetworkIdentity, SteamP2PClient,
-};
-use serde::Deserialize;
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_steam_p2p::{FilePath, NetworkIdentity, SteamP2PClient};
 
 use crate::rng::{random_float, random_point_in_donut};
 
+use animation::{zombie_animation, ZOZombieAnimationPlugin, FRAMES, ROWS};
+use archetype::{ZombieArchetype, ZombieBehavior};
+use melee::ZOMeleePlugin;
+use spit::ZOSpitPlugin;
+use state::{ZOZombieStatePlugin, ZombieState};
+
 use super::{
+    director::{Director, DirectorConfig},
     health::{Dead, Health},
+    navigation::{FlowFields, NavGrid, NavPath},
+    proximity::{index_zombies, PlayerGrid, ZombieGrid},
     Player,
 };
 
+mod animation;
+pub mod archetype;
+mod melee;
+mod spit;
+pub mod state;
+
+/// Distance at which a zombie counts a waypoint as reached and heads for the next one.
+const WAYPOINT_RADIUS: f32 = 12.;
+/// Zombies closer than this push each other apart so hordes spread out instead of stacking.
+const SEPARATION_RADIUS: f32 = 10.;
+const SEPARATION_FORCE: f32 = 12.;
+
 pub struct ZOZombiesPlugin;
 impl Plugin for ZOZombiesPlugin {
     fn build(&self, app: &mut App) {
-        app.add_networked_event::<ZombieAgroChange>().add_systems(
+        app.add_plugins((
+            ZOSpitPlugin,
+            ZOMeleePlugin,
+            ZOZombieAnimationPlugin,
+            ZOZombieStatePlugin,
+        ))
+        .add_systems(
             Update,
             (
                 handle_spawning_and_despawning.run_if(on_timer(Duration::from_millis(100))),
-                zombie_agro.run_if(on_timer(Duration::from_millis(500))),
-                handle_zombie_agro_change,
+                update_paths.run_if(on_timer(Duration::from_millis(500))),
+                handle_zombie_death,
+            ),
+        )
+        .add_systems(
+            FixedUpdate,
+            (
                 zombie_movement,
+                zombie_separation.after(index_zombies),
                 zombie_drag,
-                handle_zombie_death,
             ),
         );
     }
@@ -43,164 +70,203 @@ impl Plugin for ZOZombiesPlugin {
 #[derive(Component)]
 pub struct Zombie {
     speed: f32,
-    target: Option<Entity>,
+    state: ZombieState,
+    /// Seconds since `state` last changed.
+    state_time: f32,
 }
 
-#[derive(Event, Serialize, Clone, Deserialize)]
-pub struct ZombieAgroChange {
-    zombie_identity: NetworkId,
-    target_identity: NetworkId,
+impl Zombie {
+    fn new(speed: f32) -> Zombie {
+        Zombie {
+            speed,
+            state: ZombieState::Idle,
+            state_time: 0.,
+        }
+    }
+
+    fn set_state(&mut self, state: ZombieState) {
+        if self.state != state {
+            self.state = state;
+            self.state_time = 0.;
+        }
+    }
 }
 
+/// Keeps the number of zombies around each player at what the director asks for.
 fn handle_spawning_and_despawning(
     mut commands: Commands,
     mut client: ResMut<SteamP2PClient>,
+    mut director: ResMut<Director>,
+    config: Res<DirectorConfig>,
     spatial: SpatialQuery,
+    zombie_grid: Res<ZombieGrid>,
+    player_grid: Res<PlayerGrid>,
     players: Query<&Transform, With<Player>>,
     zombies: Query<(Entity, &Transform), With<Zombie>>,
 ) {
     if !client.is_lobby_owner().is_ok_and(|owner| owner) {
         return;
     }
-    let max_zombies = 20;
+    let max_zombies = director.zombie_cap(&config, players.iter().len());
     let min_range = 400.;
     let variation = 300.;
-    let zombie_size = 2.;
-    let mut zombies_in_range = HashSet::new();
 
     for player in players.iter() {
-        let in_range: Vec<_> = zombies
-            .iter()
-            .filter(|(_, z)| z.translation.distance(player.translation) < min_range + variation)
-            .collect();
-        let count = in_range.len();
-
-        for (zombie, _) in in_range {
-            zombies_in_range.insert(zombie);
-        }
-        if count >= max_zombies {
-            continue;
-        }
-
         let position = player.translation.xy();
-        let random_offset = random_point_in_donut(min_range, min_range + variation);
-        let sample_point = position + random_offset;
-        let shape_cast = spatial.cast_shape(
-            &Collider::circle(zombie_size),
-            sample_point,
-            0.,
-            Dir2::new(Vec2::ONE).unwrap(),
-            &ShapeCastConfig::from_max_distance(0.),
-            &SpatialQueryFilter::DEFAULT,
-        );
-        if shape_cast.is_some() {
-            continue;
-        };
-        let _ = client.instantiate(
-            FilePath("Zombie".to_owned()),
-            None,
-            Transform::from_translation(sample_point.extend(0.))
-                .with_rotation(Quat::from_rotation_z(random_float(0.0..(2. * PI)))),
-        );
+        let count = zombie_grid.count(position, min_range + variation);
+        let missing = max_zombies.saturating_sub(count);
+
+        for _ in 0..missing.min(director.spawns_per_tick(&config)) {
+            let random_offset = random_point_in_donut(min_range, min_range + variation);
+            let sample_point = position + random_offset;
+            let archetype = director.next_archetype(&config);
+            let shape_cast = spatial.cast_shape(
+                &Collider::circle(archetype.spec().radius),
+                sample_point,
+                0.,
+                Dir2::new(Vec2::ONE).unwrap(),
+                &ShapeCastConfig::from_max_distance(0.),
+                &SpatialQueryFilter::DEFAULT,
+            );
+            if shape_cast.is_some() {
+                continue;
+            };
+            let instantiated = client.instantiate(
+                FilePath("Zombie".to_owned()),
+                Some(vec![archetype.to_byte()]),
+                Transform::from_translation(sample_point.extend(0.))
+                    .with_rotation(Quat::from_rotation_z(random_float(0.0..(2. * PI)))),
+            );
+            if instantiated.is_ok() {
+                director.spawned(archetype);
+            }
+        }
     }
 
-    for (zombie, _) in zombies.iter() {
-        if !zombies_in_range.contains(&zombie) {
+    for (zombie, transform) in zombies.iter() {
+        let near_player = player_grid
+            .query(transform.translation.xy(), min_range + variation)
+            .next()
+            .is_some();
+        if !near_player {
             commands.entity(zombie).despawn();
         }
     }
 }
 
-fn zombie_agro(
-    mut agro_w: EventWriter<Networked<ZombieAgroChange>>,
-    zombies: Query<(&NetworkIdentity, &Transform, &Zombie)>,
-    players: Query<(Entity, &NetworkIdentity, &Transform), With<Player>>,
+/// Only zombies that aren't chasing, or are outside their target's flow field, search a path of
+/// their own.
+fn update_paths(
+    grid: Res<NavGrid>,
+    flow_fields: Res<FlowFields>,
+    mut zombies: Query<(&Zombie, &Transform, &mut NavPath)>,
+    targets: Query<&Transform, Without<Zombie>>,
 ) {
-    let agro_dist = 100.;
-
-    for (zombie_identity, transform, zombie) in zombies.iter() {
-        let mut closest: Option<(f32, Entity, NetworkId)> = None;
-        for (player, player_identity, player_transform) in players.iter() {
-            let dist = transform.translation.distance(player_transform.translation);
-            match &closest {
-                Some((best_dist, _, _)) if dist < *best_dist => {
-                    closest = Some((dist, player, player_identity.id.clone()));
-                }
-                None => {
-                    closest = Some((dist, player, player_identity.id.clone()));
-                }
-                _ => {}
-            }
-        }
-        let Some(best) = closest else {
+    for (zombie, transform, mut path) in zombies.iter_mut() {
+        let start = transform.translation.xy();
+        let target = zombie.state.target();
+        let Some(goal) = target
+            .and_then(|target| targets.get(target).ok())
+            .map(|t| t.translation.xy())
+            .or(zombie.state.destination())
+        else {
+            path.0.clear();
             continue;
         };
-        if zombie.target == Some(best.1) {
+        let in_field = target
+            .and_then(|target| flow_fields.get(target))
+            .is_some_and(|field| field.sample(start).is_some());
+        if in_field {
+            path.0.clear();
             continue;
         }
-        if best.0 < agro_dist {
-            agro_w.send(Networked::new(ZombieAgroChange {
-                zombie_identity: zombie_identity.id.clone(),
-                target_identity: best.2,
-            }));
-        }
-    }
-}
-
-fn handle_zombie_agro_change(
-    mut agro_r: EventReader<ZombieAgroChange>,
-    identities: Query<(Entity, &NetworkIdentity)>,
-    mut zombies: Query<&mut Zombie>,
-) {
-    for agro in agro_r.read() {
-        let Some((zombie_entity, _)) = identities
-            .iter()
-            .find(|(_, i)| i.id == agro.zombie_identity)
-        else {
-            return;
-        };
-        let Some((target, _)) = identities
-            .iter()
-            .find(|(_, i)| i.id == agro.target_identity)
-        else {
-            return;
+        path.0 = if grid.line_clear(start, goal) {
+            Vec::new()
+        } else {
+            grid.find_path(start, goal).unwrap_or_default()
         };
-        let Ok(mut zombie) = zombies.get_mut(zombie_entity) else {
-            return;
-        };
-        println!("{:?}", agro.target_identity);
-        zombie.target = Some(target);
     }
 }
 
 fn zombie_movement(
-    time: Res<Time>,
-    mut zombies: Query<(Entity, &Zombie, &mut ExternalForce)>,
+    grid: Res<NavGrid>,
+    flow_fields: Res<FlowFields>,
+    mut zombies: Query<(
+        Entity,
+        &Zombie,
+        &ZombieArchetype,
+        &mut NavPath,
+        &mut ExternalForce,
+    )>,
     mut transforms: Query<&mut Transform>,
 ) {
-    for (entity, zombie, mut force) in zombies.iter_mut() {
-        let Some(target) = zombie.target else {
-            continue;
-        };
-        let Ok(target_position) = transforms.get_mut(target).map(|t| t.translation) else {
+    for (entity, zombie, archetype, mut path, mut force) in zombies.iter_mut() {
+        let target = zombie.state.target();
+        let Some(goal) = target
+            .and_then(|target| transforms.get(target).ok())
+            .map(|t| t.translation.xy())
+            .or(zombie.state.destination())
+        else {
             continue;
         };
         let Ok(mut transform) = transforms.get_mut(entity) else {
             continue;
         };
-        let dir = (target_position - transform.translation).normalize().xy();
-        force.apply_force(dir * zombie.speed * time.delta_secs());
-        look_at_2d(&mut transform, target_position.xy());
+        while path
+            .0
+            .first()
+            .is_some_and(|waypoint| waypoint.distance(transform.translation.xy()) < WAYPOINT_RADIUS)
+        {
+            path.0.remove(0);
+        }
+        let position = transform.translation.xy();
+        let in_sight = grid.line_clear(position, goal);
+        if let ZombieBehavior::Ranged { range } = archetype.spec().behavior {
+            // Spitters hold back once they have a clear shot.
+            if target.is_some() && in_sight && position.distance(goal) < range * 0.8 {
+                look_at_2d(&mut transform, goal);
+                continue;
+            }
+        }
+        let flow = target
+            .and_then(|target| flow_fields.get(target))
+            .and_then(|field| field.sample(position))
+            .filter(|_| !in_sight);
+        // Without a flow or a path the goal is in plain sight, or out of reach of the search.
+        let dir = match flow {
+            Some(flow) => flow,
+            None => {
+                let destination = path.0.first().copied().unwrap_or(goal);
+                (destination - position).normalize_or_zero()
+            }
+        };
+        force.apply_force(dir * zombie.speed * zombie.state.speed_factor());
+        look_at_2d(&mut transform, position + dir);
     }
 }
 
-fn zombie_drag(
-    time: Res<Time>,
-    mut zombies: Query<(&LinearVelocity, &mut ExternalForce), With<Zombie>>,
+fn zombie_separation(
+    grid: Res<ZombieGrid>,
+    mut zombies: Query<(Entity, &Transform, &mut ExternalForce), With<Zombie>>,
 ) {
-    let drag = 100.;
+    for (entity, transform, mut force) in zombies.iter_mut() {
+        let position = transform.translation.xy();
+        let push = grid
+            .query(position, SEPARATION_RADIUS)
+            .filter(|(other, _)| *other != entity)
+            .fold(Vec2::ZERO, |push, (_, other)| {
+                let away = position - other;
+                let overlap = 1. - away.length() / SEPARATION_RADIUS;
+                push + away.normalize_or_zero() * overlap
+            });
+        force.apply_force(push * SEPARATION_FORCE);
+    }
+}
+
+fn zombie_drag(mut zombies: Query<(&LinearVelocity, &mut ExternalForce), With<Zombie>>) {
+    let drag = 1.5;
     for (velocity, mut force) in zombies.iter_mut() {
-        force.apply_force(-**velocity * drag * time.delta_secs());
+        force.apply_force(-**velocity * drag);
     }
 }
 
@@ -216,29 +282,37 @@ pub fn spawn_zombie(
     asset_server: &AssetServer,
     network_identity: NetworkIdentity,
     texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+    archetype: ZombieArchetype,
 ) {
-    let layout = TextureAtlasLayout::from_grid(UVec2::splat(9), 3, 1, None, None);
+    let spec = archetype.spec();
+    let layout =
+        TextureAtlasLayout::from_grid(UVec2::splat(9), FRAMES as u32, ROWS as u32, None, None);
+    let atlas_offset = spec.atlas_index.min(ROWS - 1) * FRAMES;
     let texture_atlas_layout = texture_atlas_layouts.add(layout);
 
     commands.spawn((
         network_identity,
-        Zombie {
-            speed: 20000.,
-            target: None,
-        },
+        Zombie::new(spec.speed),
+        archetype,
         transform,
         RigidBody::Dynamic,
-        Mass(0.1),
-        Collider::circle(4.),
-        Sprite::from_atlas_image(
-            asset_server.load("sprites/zombies/zombies.png"),
-            TextureAtlas {
-                layout: texture_atlas_layout,
-                index: 0,
-            },
-        ),
+        Mass(spec.mass),
+        Collider::circle(spec.radius),
+        Sprite {
+            custom_size: Some(Vec2::splat(spec.radius * 9. / 4.)),
+            color: spec.color,
+            ..Sprite::from_atlas_image(
+                asset_server.load("sprites/zombies/zombies.png"),
+                TextureAtlas {
+                    layout: texture_atlas_layout,
+                    index: atlas_offset,
+                },
+            )
+        },
         ExternalForce::default().with_persistence(false),
-        Health::new(100, true),
+        Health::new(spec.health, true),
+        NavPath::default(),
+        zombie_animation().with_offset(atlas_offset),
     ));
 }
 
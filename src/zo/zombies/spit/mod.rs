@@ -0,0 +1,196 @@
+use bevy::prelude::*;
+use bevy_steam_p2p::{
+    networked_events::{event::Networked, register::NetworkedEvents},
+    NetworkId, NetworkIdentity, SteamP2PClient,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    sprite_animation::SpriteAnimation,
+    zo::{
+        health::{ChangeHealth, Dead},
+        navigation::NavGrid,
+        Player,
+    },
+};
+
+use super::{
+    animation::ATTACK,
+    archetype::{ZombieArchetype, ZombieBehavior},
+    Zombie,
+};
+
+const SPIT_SPEED: f32 = 160.;
+const SPIT_HIT_RADIUS: f32 = 12.;
+
+pub struct ZOSpitPlugin;
+impl Plugin for ZOSpitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_networked_event::<Spit>().add_systems(
+            Update,
+            (
+                add_spit_cooldowns,
+                spit_at_targets,
+                handle_spit,
+                move_globs,
+                glob_hits,
+            ),
+        );
+    }
+}
+
+#[derive(Component)]
+struct SpitCooldown(Timer);
+
+/// A glob of spit in flight. Only the host checks it for hits.
+#[derive(Component)]
+struct SpitGlob {
+    velocity: Vec2,
+    lifetime: Timer,
+    damage: i32,
+}
+
+/// Sent by the host when a spitter fires at the position its target was at.
+#[derive(Event, Serialize, Clone, Deserialize)]
+pub struct Spit {
+    zombie_identity: NetworkId,
+    target: [f32; 2],
+}
+
+fn add_spit_cooldowns(
+    mut commands: Commands,
+    zombies: Query<(Entity, &ZombieArchetype), Added<ZombieArchetype>>,
+) {
+    for (zombie, archetype) in zombies.iter() {
+        let spec = archetype.spec();
+        if let ZombieBehavior::Ranged { .. } = spec.behavior {
+            commands
+                .entity(zombie)
+                .insert(SpitCooldown(Timer::from_seconds(
+                    spec.attack_cooldown,
+                    TimerMode::Once,
+                )));
+        }
+    }
+}
+
+fn spit_at_targets(
+    time: Res<Time>,
+    client: Res<SteamP2PClient>,
+    grid: Res<NavGrid>,
+    mut zombies: Query<
+        (
+            &NetworkIdentity,
+            &Zombie,
+            &ZombieArchetype,
+            &Transform,
+            &mut SpitCooldown,
+        ),
+        Without<Dead>,
+    >,
+    targets: Query<&Transform, With<Player>>,
+    mut spit_w: EventWriter<Networked<Spit>>,
+) {
+    if !client.is_lobby_owner().is_ok_and(|owner| owner) {
+        return;
+    }
+    for (identity, zombie, archetype, transform, mut cooldown) in zombies.iter_mut() {
+        if !cooldown.0.tick(time.delta()).finished() {
+            continue;
+        }
+        let ZombieBehavior::Ranged { range } = archetype.spec().behavior else {
+            continue;
+        };
+        let Some(target) = zombie
+            .state
+            .target()
+            .and_then(|target| targets.get(target).ok())
+        else {
+            continue;
+        };
+        let from = transform.translation.xy();
+        let to = target.translation.xy();
+        if from.distance(to) > range || !grid.line_clear(from, to) {
+            continue;
+        }
+        cooldown.0.reset();
+        spit_w.send(Networked::new(Spit {
+            zombie_identity: identity.id.clone(),
+            target: to.to_array(),
+        }));
+    }
+}
+
+fn handle_spit(
+    mut commands: Commands,
+    mut spit_r: EventReader<Spit>,
+    mut zombies: Query<
+        (
+            &NetworkIdentity,
+            &Transform,
+            &ZombieArchetype,
+            &mut SpriteAnimation,
+        ),
+        With<Zombie>,
+    >,
+) {
+    for spit in spit_r.read() {
+        let Some((_, transform, archetype, mut animation)) = zombies
+            .iter_mut()
+            .find(|(i, _, _, _)| i.id == spit.zombie_identity)
+        else {
+            continue;
+        };
+        animation.restart(ATTACK);
+        animation.speed = 1.;
+        let from = transform.translation.xy();
+        let offset = Vec2::from_array(spit.target) - from;
+        commands.spawn((
+            SpitGlob {
+                velocity: offset.normalize_or_zero() * SPIT_SPEED,
+                lifetime: Timer::from_seconds(offset.length() / SPIT_SPEED, TimerMode::Once),
+                damage: archetype.spec().damage,
+            },
+            Transform::from_translation(from.extend(2.)),
+            Sprite::from_color(Color::srgb(0.6, 0.9, 0.2), Vec2::splat(3.)),
+        ));
+    }
+}
+
+fn move_globs(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut globs: Query<(Entity, &mut SpitGlob, &mut Transform)>,
+) {
+    for (entity, mut glob, mut transform) in globs.iter_mut() {
+        transform.translation += (glob.velocity * time.delta_secs()).extend(0.);
+        if glob.lifetime.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn glob_hits(
+    mut commands: Commands,
+    client: Res<SteamP2PClient>,
+    globs: Query<(Entity, &SpitGlob, &Transform)>,
+    players: Query<(&NetworkIdentity, &Transform), With<Player>>,
+    mut change_health_w: EventWriter<Networked<ChangeHealth>>,
+) {
+    if !client.is_lobby_owner().is_ok_and(|owner| owner) {
+        return;
+    }
+    for (entity, glob, glob_transform) in globs.iter() {
+        let Some((identity, _)) = players.iter().find(|(_, t)| {
+            t.translation.xy().distance(glob_transform.translation.xy()) < SPIT_HIT_RADIUS
+        }) else {
+            continue;
+        };
+        change_health_w.send(Networked::new(ChangeHealth {
+            network_id: identity.id.clone(),
+            change: -glob.damage,
+            source: None,
+        }));
+        commands.entity(entity).despawn();
+    }
+}
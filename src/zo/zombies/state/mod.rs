@@ -0,0 +1,333 @@
+use std::time::Duration;
+
+use avian2d::prelude::{RigidBody, Sensor, SpatialQuery, SpatialQueryFilter};
+use bevy::{prelude::*, time::common_conditions::on_timer, utils::HashSet};
+use bevy_steam_p2p::{
+    networked_events::{event::Networked, register::NetworkedEvents},
+    NetworkId, NetworkIdentity, SteamP2PClient,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    rng::{random_float, random_point_in_donut},
+    zo::{
+        health::Dead,
+        navigation::NavGrid,
+        noise::Noise,
+        proximity::{PlayerGrid, ZombieGrid},
+        Player,
+    },
+};
+
+use super::Zombie;
+
+/// How far a zombie sees when nothing is in the way.
+const SIGHT_DISTANCE: f32 = 150.;
+/// A chase is given up once the target gets this far away.
+const LEASH_DISTANCE: f32 = 400.;
+/// Center to center, so it reaches a car's bumper as well as a character.
+pub const ATTACK_RANGE: f32 = 28.;
+const ARRIVE_DISTANCE: f32 = 16.;
+const WANDER_MIN: f32 = 40.;
+const WANDER_MAX: f32 = 120.;
+/// Chance per think that an idle zombie starts wandering.
+const WANDER_CHANCE: f32 = 0.2;
+/// How long a zombie searches around the last known position before giving up.
+const LOST_SECONDS: f32 = 4.;
+/// Walks that take longer than this are abandoned, in case the place can't be reached.
+const GIVE_UP_SECONDS: f32 = 12.;
+/// Zombies already investigating this close to a noise keep going instead of starting over.
+const SAME_NOISE_DISTANCE: f32 = 48.;
+const THINK_INTERVAL: Duration = Duration::from_millis(500);
+
+pub struct ZOZombieStatePlugin;
+impl Plugin for ZOZombieStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_networked_event::<ZombieStateChange>().add_systems(
+            Update,
+            (
+                tick_state_time,
+                think.run_if(on_timer(THINK_INTERVAL)),
+                hear_noises,
+                handle_zombie_state_change,
+            ),
+        );
+    }
+}
+
+/// What a zombie is doing. Decided by the host and broadcast with `ZombieStateChange`.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum ZombieState {
+    #[default]
+    Idle,
+    Wander(Vec2),
+    /// Heading to a place where something was last seen or heard.
+    Investigate(Vec2),
+    /// Going after a target, along with where the zombie last saw it.
+    Chase(Entity, Vec2),
+    Attack(Entity, Vec2),
+    /// Searching around the last known position after losing the target.
+    Lost(Vec2),
+}
+
+impl ZombieState {
+    pub fn target(self) -> Option<Entity> {
+        match self {
+            ZombieState::Chase(target, _) | ZombieState::Attack(target, _) => Some(target),
+            _ => None,
+        }
+    }
+
+    /// Remembers `at` as where the target was last seen.
+    fn see_target_at(&mut self, at: Vec2) {
+        if let ZombieState::Chase(_, last_seen) | ZombieState::Attack(_, last_seen) = self {
+            *last_seen = at;
+        }
+    }
+
+    /// Place the zombie walks to when it has no target to chase.
+    pub fn destination(self) -> Option<Vec2> {
+        match self {
+            ZombieState::Wander(point)
+            | ZombieState::Investigate(point)
+            | ZombieState::Lost(point) => Some(point),
+            _ => None,
+        }
+    }
+
+    /// Share of the zombie's speed it moves at.
+    pub fn speed_factor(self) -> f32 {
+        match self {
+            ZombieState::Idle => 0.,
+            ZombieState::Wander(_) | ZombieState::Lost(_) => 0.4,
+            ZombieState::Investigate(_) => 0.7,
+            ZombieState::Chase(..) | ZombieState::Attack(..) => 1.,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+enum NetworkedState {
+    Idle,
+    Wander([f32; 2]),
+    Investigate([f32; 2]),
+    Chase(NetworkId, [f32; 2]),
+    Attack(NetworkId, [f32; 2]),
+    Lost([f32; 2]),
+}
+
+#[derive(Event, Serialize, Clone, Deserialize)]
+pub struct ZombieStateChange {
+    zombie_identity: NetworkId,
+    state: NetworkedState,
+}
+
+fn tick_state_time(time: Res<Time>, mut zombies: Query<&mut Zombie>) {
+    for mut zombie in zombies.iter_mut() {
+        zombie.state_time += time.delta_secs();
+    }
+}
+
+/// Only the host decides transitions, so every peer agrees on what each zombie is doing. Where a
+/// chased target was last seen is only kept up to date on the host, since only it needs it.
+fn think(
+    client: Res<SteamP2PClient>,
+    grid: Res<NavGrid>,
+    spatial: SpatialQuery,
+    obstacles: Query<&RigidBody, Without<Sensor>>,
+    player_grid: Res<PlayerGrid>,
+    mut zombies: Query<(&NetworkIdentity, &Transform, &mut Zombie), Without<Dead>>,
+    players: Query<(Entity, &NetworkIdentity, &Transform), With<Player>>,
+    mut state_w: EventWriter<Networked<ZombieStateChange>>,
+) {
+    if !client.is_lobby_owner().is_ok_and(|owner| owner) {
+        return;
+    }
+
+    for (zombie_identity, transform, mut zombie) in zombies.iter_mut() {
+        let position = transform.translation.xy();
+        let spotted = player_grid
+            .query(position, SIGHT_DISTANCE)
+            .filter(|(_, p)| can_see(&spatial, &obstacles, position, *p))
+            .filter_map(|(entity, p)| players.get(entity).ok().map(|(_, i, _)| (entity, i, p)))
+            .min_by(|(_, _, a), (_, _, b)| {
+                a.distance_squared(position)
+                    .total_cmp(&b.distance_squared(position))
+            });
+        let target_position = |target: Entity| {
+            players
+                .get(target)
+                .ok()
+                .map(|(_, identity, t)| (identity.id.clone(), t.translation.xy()))
+        };
+
+        let next = match zombie.state {
+            ZombieState::Chase(target, last_seen) | ZombieState::Attack(target, last_seen) => {
+                match target_position(target) {
+                    Some((_, at)) if at.distance(position) > LEASH_DISTANCE => {
+                        Some(NetworkedState::Lost(last_seen.to_array()))
+                    }
+                    Some((id, at)) => {
+                        let seen = at.distance(position) < SIGHT_DISTANCE
+                            && can_see(&spatial, &obstacles, position, at);
+                        let last_seen = if seen { at } else { last_seen };
+                        zombie.state.see_target_at(last_seen);
+                        if at.distance(position) < ATTACK_RANGE {
+                            Some(NetworkedState::Attack(id, last_seen.to_array()))
+                        } else {
+                            Some(NetworkedState::Chase(id, last_seen.to_array()))
+                        }
+                    }
+                    None => Some(NetworkedState::Lost(last_seen.to_array())),
+                }
+            }
+            _ if spotted.is_some() => spotted
+                .map(|(_, identity, at)| NetworkedState::Chase(identity.id.clone(), at.to_array())),
+            ZombieState::Investigate(at)
+                if at.distance(position) < ARRIVE_DISTANCE
+                    || zombie.state_time > GIVE_UP_SECONDS =>
+            {
+                Some(NetworkedState::Lost(at.to_array()))
+            }
+            ZombieState::Wander(at)
+                if at.distance(position) < ARRIVE_DISTANCE
+                    || zombie.state_time > GIVE_UP_SECONDS =>
+            {
+                Some(NetworkedState::Idle)
+            }
+            ZombieState::Lost(_) if zombie.state_time > LOST_SECONDS => Some(NetworkedState::Idle),
+            ZombieState::Idle if random_float(0.0..1.) < WANDER_CHANCE => {
+                let point = position + random_point_in_donut(WANDER_MIN, WANDER_MAX);
+                grid.line_clear(position, point)
+                    .then_some(NetworkedState::Wander(point.to_array()))
+            }
+            _ => None,
+        };
+
+        let Some(next) = next else {
+            continue;
+        };
+        if matches_state(&next, zombie.state, &players) {
+            continue;
+        }
+        state_w.send(Networked::new(ZombieStateChange {
+            zombie_identity: zombie_identity.id.clone(),
+            state: next,
+        }));
+    }
+}
+
+/// Whether no building stands between `from` and `to`.
+fn can_see(
+    spatial: &SpatialQuery,
+    obstacles: &Query<&RigidBody, Without<Sensor>>,
+    from: Vec2,
+    to: Vec2,
+) -> bool {
+    let Ok(direction) = Dir2::new(to - from) else {
+        return true;
+    };
+    spatial
+        .cast_ray_predicate(
+            from,
+            direction,
+            from.distance(to),
+            true,
+            &SpatialQueryFilter::default(),
+            &|entity| obstacles.get(entity).is_ok_and(RigidBody::is_static),
+        )
+        .is_none()
+}
+
+/// Zombies that aren't already after someone go to check out what they heard.
+fn hear_noises(
+    client: Res<SteamP2PClient>,
+    mut noise_r: EventReader<Noise>,
+    grid: Res<ZombieGrid>,
+    zombies: Query<(&NetworkIdentity, &Zombie), Without<Dead>>,
+    mut state_w: EventWriter<Networked<ZombieStateChange>>,
+) {
+    if !client.is_lobby_owner().is_ok_and(|owner| owner) {
+        noise_r.clear();
+        return;
+    }
+    // States only change once the events are handled, so each zombie hears one noise per batch.
+    let mut heard = HashSet::new();
+    for noise in noise_r.read() {
+        let source = Vec2::from_array(noise.position);
+        for (entity, _) in grid.query(source, noise.radius) {
+            let Ok((identity, zombie)) = zombies.get(entity) else {
+                continue;
+            };
+            let keeps_going = match zombie.state {
+                ZombieState::Investigate(at) => at.distance(source) < SAME_NOISE_DISTANCE,
+                state => state.target().is_some(),
+            };
+            if keeps_going || !heard.insert(entity) {
+                continue;
+            }
+            state_w.send(Networked::new(ZombieStateChange {
+                zombie_identity: identity.id.clone(),
+                state: NetworkedState::Investigate(noise.position),
+            }));
+        }
+    }
+}
+
+fn matches_state(
+    next: &NetworkedState,
+    current: ZombieState,
+    players: &Query<(Entity, &NetworkIdentity, &Transform), With<Player>>,
+) -> bool {
+    let is_target = |id: &NetworkId, target: Entity| {
+        players
+            .get(target)
+            .is_ok_and(|(_, identity, _)| identity.id == *id)
+    };
+    match (next, current) {
+        (NetworkedState::Idle, ZombieState::Idle) => true,
+        (NetworkedState::Chase(id, _), ZombieState::Chase(target, _))
+        | (NetworkedState::Attack(id, _), ZombieState::Attack(target, _)) => is_target(id, target),
+        _ => false,
+    }
+}
+
+fn handle_zombie_state_change(
+    mut state_r: EventReader<ZombieStateChange>,
+    identities: Query<(Entity, &NetworkIdentity)>,
+    mut zombies: Query<&mut Zombie>,
+) {
+    let entity_of = |id: &NetworkId| {
+        identities
+            .iter()
+            .find(|(_, i)| i.id == *id)
+            .map(|(entity, _)| entity)
+    };
+
+    for change in state_r.read() {
+        let Some(mut zombie) =
+            entity_of(&change.zombie_identity).and_then(|entity| zombies.get_mut(entity).ok())
+        else {
+            continue;
+        };
+        let state = match &change.state {
+            NetworkedState::Idle => ZombieState::Idle,
+            NetworkedState::Wander(at) => ZombieState::Wander(Vec2::from_array(*at)),
+            NetworkedState::Investigate(at) => ZombieState::Investigate(Vec2::from_array(*at)),
+            NetworkedState::Lost(at) => ZombieState::Lost(Vec2::from_array(*at)),
+            NetworkedState::Chase(id, at) => {
+                let Some(target) = entity_of(id) else {
+                    continue;
+                };
+                ZombieState::Chase(target, Vec2::from_array(*at))
+            }
+            NetworkedState::Attack(id, at) => {
+                let Some(target) = entity_of(id) else {
+                    continue;
+                };
+                ZombieState::Attack(target, Vec2::from_array(*at))
+            }
+        };
+        zombie.set_state(state);
+    }
+}